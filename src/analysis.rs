@@ -0,0 +1,814 @@
+use std::collections::HashMap;
+use crate::basicstrategy::*;
+use crate::cardutils::*;
+use crate::counting::*;
+use crate::gamelogic::*;
+
+// Number of cards of each rank left in the shoe, indexed A, 2, ..., 9, T like
+// counting::rank_index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Composition {
+    pub counts: [u32; 10],
+    pub total: u32,
+}
+
+impl Composition {
+    #[inline(always)]
+    pub fn new(decks_in_shoe: u8) -> Self {
+        let decks = decks_in_shoe as u32;
+        let mut counts = [4 * decks; 10];
+        counts[9] = 16 * decks;
+        Composition {
+            counts,
+            total: 52 * decks,
+        }
+    }
+
+    #[inline(always)]
+    pub fn remove(&mut self, rank: usize) {
+        self.counts[rank] -= 1;
+        self.total -= 1;
+    }
+
+    #[inline(always)]
+    pub fn add(&mut self, rank: usize) {
+        self.counts[rank] += 1;
+        self.total += 1;
+    }
+
+    // Full shoe less the cards already seen
+    #[inline(always)]
+    pub fn unseen(decks_in_shoe: u8, seen: &[Card]) -> Self {
+        let mut shoe = Self::new(decks_in_shoe);
+        for card in seen {
+            shoe.remove(rank_index(card.get_rank()));
+        }
+        shoe
+    }
+}
+
+// A hand as a multiset of ranks, with the key packing five bits of count per rank.
+// Splits also use SPLIT_TENS as an eleventh rank
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct HandState {
+    hard: u32,
+    aces: u32,
+    key: u64,
+}
+
+impl HandState {
+    #[inline(always)]
+    pub(crate) fn from_cards(cards: &[Card]) -> Self {
+        cards.iter().fold(Self::default(), |hand, card| {
+            hand.push(rank_index(card.get_rank()))
+        })
+    }
+
+    #[inline(always)]
+    pub(crate) fn push(self, rank: usize) -> Self {
+        HandState {
+            hard: self.hard + rank.min(9) as u32 + 1,
+            aces: self.aces + (rank == 0) as u32,
+            key: self.key + (1 << (5 * rank)),
+        }
+    }
+
+    #[inline(always)]
+    fn count(&self, rank: usize) -> u32 {
+        ((self.key >> (5 * rank)) & 31) as u32
+    }
+
+    #[inline(always)]
+    fn len(&self) -> u32 {
+        (0..=SPLIT_TENS).map(|rank| self.count(rank)).sum()
+    }
+
+    #[inline(always)]
+    pub(crate) fn is_soft(&self) -> bool {
+        self.aces > 0 && self.hard + 10 <= 21
+    }
+
+    #[inline(always)]
+    pub(crate) fn total(&self) -> u32 {
+        if self.is_soft() {
+            self.hard + 10
+        } else {
+            self.hard
+        }
+    }
+}
+
+// Probabilities of the dealer finishing on 17, 18, 19, 20, 21 or busting, given
+// that the dealer has already peeked and doesn't have blackjack
+type DealerOutcomes = [f64; 6];
+
+fn dealer_outcomes(shoe: &mut Composition, upcard: usize, hit_soft_17: bool) -> DealerOutcomes {
+    let mut cache = HashMap::new();
+    let excluded = match upcard {
+        0 => Some(9),
+        9 => Some(0),
+        _ => None,
+    };
+    let total = shoe.total - excluded.map_or(0, |rank| shoe.counts[rank]);
+    let mut outcomes = [0.0; 6];
+    for rank in 0..10 {
+        if Some(rank) == excluded || shoe.counts[rank] == 0 {
+            continue;
+        }
+        let probability = shoe.counts[rank] as f64 / total as f64;
+        let hand = HandState::default().push(upcard).push(rank);
+        shoe.remove(rank);
+        let drawn = dealer_draw(shoe, hand, hit_soft_17, &mut cache);
+        shoe.add(rank);
+        for (outcome, drawn) in outcomes.iter_mut().zip(drawn) {
+            *outcome += probability * drawn;
+        }
+    }
+    outcomes
+}
+
+fn dealer_draw(
+    shoe: &mut Composition,
+    hand: HandState,
+    hit_soft_17: bool,
+    cache: &mut HashMap<u64, DealerOutcomes>,
+) -> DealerOutcomes {
+    let total = hand.total();
+    let mut outcomes = [0.0; 6];
+    if total > 21 {
+        outcomes[5] = 1.0;
+        return outcomes;
+    }
+    if total >= 18 || (total == 17 && !(hit_soft_17 && hand.is_soft())) {
+        outcomes[(total - 17) as usize] = 1.0;
+        return outcomes;
+    }
+    if let Some(outcomes) = cache.get(&hand.key) {
+        return *outcomes;
+    }
+    for rank in 0..10 {
+        if shoe.counts[rank] == 0 {
+            continue;
+        }
+        let probability = shoe.counts[rank] as f64 / shoe.total as f64;
+        shoe.remove(rank);
+        let drawn = dealer_draw(shoe, hand.push(rank), hit_soft_17, cache);
+        shoe.add(rank);
+        for (outcome, drawn) in outcomes.iter_mut().zip(drawn) {
+            *outcome += probability * drawn;
+        }
+    }
+    cache.insert(hand.key, outcomes);
+    outcomes
+}
+
+// Exact expected values for hands played against one dealer upcard, with every
+// card in the player's hand removed from the shoe
+pub(crate) struct Evaluator<'a> {
+    rules: &'a GameRules,
+    shoe: Composition,
+    upcard: usize,
+    dealer_cache: HashMap<u64, DealerOutcomes>,
+    hand_cache: HashMap<u64, f64>,
+}
+
+impl<'a> Evaluator<'a> {
+    pub(crate) fn new(rules: &'a GameRules, shoe: Composition, upcard: usize) -> Self {
+        Evaluator {
+            rules,
+            shoe,
+            upcard,
+            dealer_cache: HashMap::new(),
+            hand_cache: HashMap::new(),
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn remaining(&self, hand: &HandState) -> Composition {
+        let mut shoe = self.shoe;
+        for rank in 0..10 {
+            let count = hand.count(rank);
+            shoe.counts[rank] -= count;
+            shoe.total -= count;
+        }
+        shoe
+    }
+
+    // Chance of each rank being the next card, given that the dealer has peeked
+    // and doesn't have blackjack, so the hole card can't be the card that makes it
+    #[inline(always)]
+    pub(crate) fn draw_probabilities(&self, shoe: &Composition) -> [f64; 10] {
+        let excluded = match self.upcard {
+            0 => 9,
+            9 => 0,
+            _ => return shoe.counts.map(|count| count as f64 / shoe.total as f64),
+        };
+        let mut weights = [0.0; 10];
+        let mut sum = 0.0;
+        for (rank, weight) in weights.iter_mut().enumerate() {
+            let hole_cards = shoe.total - 1 - shoe.counts[excluded] + (rank == excluded) as u32;
+            *weight = shoe.counts[rank] as f64 * hole_cards as f64;
+            sum += *weight;
+        }
+        weights.map(|weight| weight / sum)
+    }
+
+    // Folds in a dealer blackjack the players couldn't know about before acting,
+    // given the value of a play when the dealer doesn't have one and how many bets
+    // the play puts out
+    #[inline(always)]
+    pub(crate) fn settle_hole_card(&self, hand: &HandState, value: f64, stake: f64) -> f64 {
+        let excluded = match (self.rules.hole_card, self.upcard) {
+            (HoleCard::Peek, _) => return value,
+            (_, 0) => 9,
+            (_, 9) => 0,
+            _ => return value,
+        };
+        let shoe = self.remaining(hand);
+        let blackjack = shoe.counts[excluded] as f64 / shoe.total as f64;
+        let lost = match self.rules.hole_card {
+            HoleCard::OriginalBetsOnly => 1.0,
+            _ => stake,
+        };
+        (1.0 - blackjack) * value - blackjack * lost
+    }
+
+    pub(crate) fn stand(&mut self, hand: HandState) -> f64 {
+        let total = hand.total();
+        if total > 21 {
+            return -1.0;
+        }
+        let outcomes = match self.dealer_cache.get(&hand.key) {
+            Some(outcomes) => *outcomes,
+            None => {
+                let mut shoe = self.remaining(&hand);
+                let outcomes = dealer_outcomes(&mut shoe, self.upcard, self.rules.hit_soft_17);
+                self.dealer_cache.insert(hand.key, outcomes);
+                outcomes
+            }
+        };
+        let mut value = outcomes[5];
+        for (dealer_total, probability) in (17..=21).zip(outcomes) {
+            if total > dealer_total {
+                value += probability;
+            } else if total < dealer_total {
+                value -= probability;
+            }
+        }
+        value
+    }
+
+    pub(crate) fn hit(&mut self, hand: HandState) -> f64 {
+        let probabilities = self.draw_probabilities(&self.remaining(&hand));
+        let mut value = 0.0;
+        for (rank, probability) in probabilities.into_iter().enumerate() {
+            if probability > 0.0 {
+                value += probability * self.best(hand.push(rank));
+            }
+        }
+        value
+    }
+
+    // Hitting or standing, whichever is worth more, or doubling too when the rules
+    // allow it on more than two cards. Split hands are valued by SplitEvaluator
+    pub(crate) fn best(&mut self, hand: HandState) -> f64 {
+        let total = hand.total();
+        if total > 21 {
+            return -1.0;
+        }
+        if total == 21 {
+            return self.stand(hand);
+        }
+        if let Some(value) = self.hand_cache.get(&hand.key) {
+            return *value;
+        }
+        let mut value = self.stand(hand).max(self.hit(hand));
+        if self.rules.doubling == DoubleRule::AnyCards {
+            value = value.max(self.double(hand));
+        }
+        self.hand_cache.insert(hand.key, value);
+        value
+    }
+
+    pub(crate) fn double(&mut self, hand: HandState) -> f64 {
+        let probabilities = self.draw_probabilities(&self.remaining(&hand));
+        let mut value = 0.0;
+        for (rank, probability) in probabilities.into_iter().enumerate() {
+            if probability > 0.0 {
+                value += probability * self.stand(hand.push(rank));
+            }
+        }
+        2.0 * value
+    }
+}
+
+// When a ten is split and unlike tens can't be, the tens of its own rank go in
+// this bucket so they can be told apart from the tens that can't be split again
+const SPLIT_TENS: usize = 10;
+
+// Chance of the next pairs + others cards coming out in one given order of pair
+// cards and other cards, with pair_cards of the total left in the shoe
+#[inline(always)]
+fn run_probability(pairs: u32, others: u32, pair_cards: u32, total: u32) -> f64 {
+    if pairs > pair_cards || others > total - pair_cards {
+        return 0.0;
+    }
+    let mut probability = 1.0;
+    for drawn in 0..pairs {
+        probability *= (pair_cards - drawn) as f64 / (total - drawn) as f64;
+    }
+    for drawn in 0..others {
+        probability *= (total - pair_cards - drawn) as f64 / (total - pairs - drawn) as f64;
+    }
+    probability
+}
+
+// Every way the split hands' second cards can come out as (hands, pair cards,
+// other cards, orders). Each hand in turn draws until it has a second card, and
+// a pair card drawn while there's room for another hand starts one instead
+fn second_card_runs(max_hands: u32) -> Vec<(u32, u32, u32, f64)> {
+    let mut runs = vec![];
+    // (hands, hands with a second card, other cards) to the orders reaching it
+    let mut layer = HashMap::from([((2, 0, 0), 1.0)]);
+    while !layer.is_empty() {
+        let mut next = HashMap::new();
+        for ((hands, dealt, others), orders) in layer {
+            if dealt == hands {
+                runs.push((hands, hands - 2 + dealt - others, others, orders));
+                continue;
+            }
+            let pair = match hands < max_hands {
+                true => (hands + 1, dealt, others),
+                false => (hands, dealt + 1, others),
+            };
+            *next.entry(pair).or_insert(0.0) += orders;
+            *next.entry((hands, dealt + 1, others + 1)).or_insert(0.0) += orders;
+        }
+        layer = next;
+    }
+    runs
+}
+
+// Chances of the dealer finishing on 17, 18, 19, 20, 21, busting or having
+// blackjack, each weighted by split_weights() once the dealer is done
+type SplitDealerOutcomes = [[f64; 7]; 2];
+
+// Values the hands of a split for split_value().
+//
+// Any hand's cards and the dealer's are as likely to come out before the other
+// hands' cards as after them, so each hand is valued as if it were dealt first,
+// followed by the dealer. The other hands only matter through how their second
+// cards come out, since that decides how many hands there are: a pair card starts
+// a new hand and any other card doesn't. split_weights() adds up the chances of
+// that for the cards left once a hand and the dealer are done. The first of each
+// pair of values is for hands whose second card isn't a pair card, the second
+// for hands left holding a pair they can't split again
+struct SplitEvaluator<'a> {
+    rules: &'a GameRules,
+    // Cards left once the pair and upcard are out
+    counts: [u32; 11],
+    total: u32,
+    upcard: usize,
+    // Bucket of the cards that start new hands, which each hand starts with
+    pair: usize,
+    can_hit: bool,
+    can_double: bool,
+    runs: Vec<(u32, u32, u32, f64)>,
+    weight_cache: HashMap<(u32, u32), [f64; 2]>,
+    dealer_cache: HashMap<u64, SplitDealerOutcomes>,
+    hand_cache: HashMap<(u64, usize), f64>,
+}
+
+impl<'a> SplitEvaluator<'a> {
+    // Expected number of hands like the one just played, times the chance of the
+    // other hands' second cards coming out that way from what's left
+    fn split_weights(&mut self, pair_cards: u32, total: u32) -> [f64; 2] {
+        if let Some(weights) = self.weight_cache.get(&(pair_cards, total)) {
+            return *weights;
+        }
+        let mut weights = [0.0; 2];
+        for &(hands, pairs, others, orders) in self.runs.iter() {
+            let stuck = pairs - (hands - 2);
+            if others > 0 {
+                let probability = run_probability(pairs, others - 1, pair_cards, total);
+                weights[0] += orders * others as f64 * probability;
+            }
+            if stuck > 0 {
+                let probability = run_probability(pairs - 1, others, pair_cards, total);
+                weights[1] += orders * stuck as f64 * probability;
+            }
+        }
+        self.weight_cache.insert((pair_cards, total), weights);
+        weights
+    }
+
+    // Cards left once the hand is out, apart from its first card which came
+    // from the pair or started a new hand
+    #[inline(always)]
+    fn remaining(&self, hand: &HandState) -> ([u32; 11], u32) {
+        let mut counts = self.counts;
+        let mut total = self.total + 1;
+        counts[self.pair] += 1;
+        for (rank, count) in counts.iter_mut().enumerate() {
+            *count -= hand.count(rank);
+            total -= hand.count(rank);
+        }
+        (counts, total)
+    }
+
+    // Hole cards that give the dealer blackjack, which the hole card rule deals
+    // with unless there's no hole card at all
+    #[inline(always)]
+    fn settled_by_hole_card(&self, rank: usize) -> bool {
+        let ten = rank >= 9;
+        let blackjack = (self.upcard == 0 && ten) || (self.upcard == 9 && rank == 0);
+        blackjack && self.rules.hole_card != HoleCard::NoHoleCard
+    }
+
+    fn dealer(&mut self, hand: &HandState) -> SplitDealerOutcomes {
+        if let Some(outcomes) = self.dealer_cache.get(&hand.key) {
+            return *outcomes;
+        }
+        let (mut counts, mut total) = self.remaining(hand);
+        let mut cache = HashMap::new();
+        let mut outcomes = [[0.0; 7]; 2];
+        let upcard = HandState::default().push(self.upcard);
+        for rank in 0..=SPLIT_TENS {
+            if counts[rank] == 0 || self.settled_by_hole_card(rank) {
+                continue;
+            }
+            let probability = counts[rank] as f64 / total as f64;
+            let dealer = upcard.push(rank);
+            counts[rank] -= 1;
+            total -= 1;
+            let drawn = match dealer.total() == 21 {
+                true => {
+                    let weights = self.split_weights(counts[self.pair], total);
+                    let mut drawn = [[0.0; 7]; 2];
+                    drawn[0][6] = weights[0];
+                    drawn[1][6] = weights[1];
+                    drawn
+                }
+                false => self.dealer_draw(&mut counts, &mut total, dealer, &mut cache),
+            };
+            counts[rank] += 1;
+            total += 1;
+            for (outcomes, drawn) in outcomes.iter_mut().zip(drawn) {
+                for (outcome, drawn) in outcomes.iter_mut().zip(drawn) {
+                    *outcome += probability * drawn;
+                }
+            }
+        }
+        self.dealer_cache.insert(hand.key, outcomes);
+        outcomes
+    }
+
+    fn dealer_draw(
+        &mut self,
+        counts: &mut [u32; 11],
+        total: &mut u32,
+        dealer: HandState,
+        cache: &mut HashMap<u64, SplitDealerOutcomes>,
+    ) -> SplitDealerOutcomes {
+        let dealer_total = dealer.total();
+        let hit_soft_17 = self.rules.hit_soft_17 && dealer.is_soft();
+        if dealer_total > 21 || dealer_total >= 18 || (dealer_total == 17 && !hit_soft_17) {
+            let weights = self.split_weights(counts[self.pair], *total);
+            let outcome = (dealer_total.min(22) - 17) as usize;
+            let mut outcomes = [[0.0; 7]; 2];
+            outcomes[0][outcome] = weights[0];
+            outcomes[1][outcome] = weights[1];
+            return outcomes;
+        }
+        if let Some(outcomes) = cache.get(&dealer.key) {
+            return *outcomes;
+        }
+        let mut outcomes = [[0.0; 7]; 2];
+        for rank in 0..=SPLIT_TENS {
+            if counts[rank] == 0 {
+                continue;
+            }
+            let probability = counts[rank] as f64 / *total as f64;
+            counts[rank] -= 1;
+            *total -= 1;
+            let drawn = self.dealer_draw(counts, total, dealer.push(rank), cache);
+            counts[rank] += 1;
+            *total += 1;
+            for (outcomes, drawn) in outcomes.iter_mut().zip(drawn) {
+                for (outcome, drawn) in outcomes.iter_mut().zip(drawn) {
+                    *outcome += probability * drawn;
+                }
+            }
+        }
+        cache.insert(dealer.key, outcomes);
+        outcomes
+    }
+
+    // Standing with stake bets out, or losing them on a bust
+    fn stand(&mut self, kind: usize, hand: HandState, stake: f64) -> f64 {
+        let total = hand.total();
+        if total > 21 {
+            // Whatever the dealer draws after the hole card doesn't matter
+            let (counts, cards) = self.remaining(&hand);
+            let mut weight = 0.0;
+            for rank in 0..=SPLIT_TENS {
+                if counts[rank] == 0 || self.settled_by_hole_card(rank) {
+                    continue;
+                }
+                let pair_cards = counts[self.pair] - (rank == self.pair) as u32;
+                let probability = counts[rank] as f64 / cards as f64;
+                weight += probability * self.split_weights(pair_cards, cards - 1)[kind];
+            }
+            return -stake * weight;
+        }
+        let outcomes = self.dealer(&hand)[kind];
+        let mut value = outcomes[5] - outcomes[6];
+        for (dealer_total, probability) in (17..=21).zip(outcomes) {
+            if total > dealer_total {
+                value += probability;
+            } else if total < dealer_total {
+                value -= probability;
+            }
+        }
+        stake * value
+    }
+
+    fn hit(&mut self, kind: usize, hand: HandState) -> f64 {
+        let (counts, total) = self.remaining(&hand);
+        let mut value = 0.0;
+        for (rank, count) in counts.into_iter().enumerate() {
+            if count > 0 {
+                let probability = count as f64 / total as f64;
+                value += probability * self.best(kind, hand.push(rank));
+            }
+        }
+        value
+    }
+
+    fn double(&mut self, kind: usize, hand: HandState) -> f64 {
+        let (counts, total) = self.remaining(&hand);
+        let mut value = 0.0;
+        for (rank, count) in counts.into_iter().enumerate() {
+            if count > 0 {
+                let probability = count as f64 / total as f64;
+                value += probability * self.stand(kind, hand.push(rank), 2.0);
+            }
+        }
+        value
+    }
+
+    fn best(&mut self, kind: usize, hand: HandState) -> f64 {
+        let total = hand.total();
+        if total >= 21 {
+            return self.stand(kind, hand, 1.0);
+        }
+        if let Some(value) = self.hand_cache.get(&(hand.key, kind)) {
+            return *value;
+        }
+        let mut value = self.stand(kind, hand, 1.0);
+        if self.can_hit {
+            value = value.max(self.hit(kind, hand));
+        }
+        if self.can_double && self.rules.doubling.allows(hand.len() as usize, total as u8) {
+            value = value.max(self.double(kind, hand));
+        }
+        self.hand_cache.insert((hand.key, kind), value);
+        value
+    }
+}
+
+// Exact value of splitting a pair of rank, resplitting whenever the rules allow,
+// with the dealer's blackjack settled by the hole card rule. Each split hand is
+// played the best way given its own cards, not the other hands'.
+//
+// A composition doesn't tell the ten ranks apart, so when only tens of the same
+// rank can be split again the tens left are taken to be spread evenly over
+// them, to the nearest card
+pub(crate) fn split_value(rules: &GameRules, shoe: Composition, upcard: usize, rank: usize) -> f64 {
+    let mut counts = [0; 11];
+    counts[..10].copy_from_slice(&shoe.counts);
+    let mut pair = rank;
+    if rank == 9 && !rules.split_unlike_tens {
+        pair = SPLIT_TENS;
+        counts[SPLIT_TENS] = ((counts[9] + 2) / 4).min(counts[9]);
+        counts[9] -= counts[SPLIT_TENS];
+    }
+    counts[pair] -= 2;
+    let total = shoe.total - 2;
+    let is_ace = rank == 0;
+    let max_hands = match is_ace {
+        true if !rules.resplit_aces => Some(2),
+        true => rules.max_ace_hands,
+        false => rules.max_hands,
+    };
+    let max_hands = max_hands.map_or(u32::MAX, |max_hands| max_hands.max(2) as u32);
+    let mut evaluator = SplitEvaluator {
+        rules,
+        counts,
+        total,
+        upcard,
+        pair,
+        can_hit: !is_ace || rules.hit_split_aces,
+        can_double: rules.double_after_split && (!is_ace || rules.double_split_aces),
+        runs: second_card_runs(max_hands.min(2 + counts[pair])),
+        weight_cache: HashMap::new(),
+        dealer_cache: HashMap::new(),
+        hand_cache: HashMap::new(),
+    };
+
+    let first = HandState::default().push(pair);
+    let mut value = 0.0;
+    for (second, count) in counts.into_iter().enumerate() {
+        if count > 0 {
+            let probability = count as f64 / total as f64;
+            let kind = (second == pair) as usize;
+            value += probability * evaluator.best(kind, first.push(second));
+        }
+    }
+    // Hands were only valued against hole cards that aren't blackjack. Peeking
+    // rules those out, and original bets only loses just the one bet to them
+    let blackjack = (0..=SPLIT_TENS)
+        .filter(|&rank| evaluator.settled_by_hole_card(rank))
+        .map(|rank| counts[rank] as f64 / total as f64)
+        .sum::<f64>();
+    match rules.hole_card {
+        HoleCard::Peek => value / (1.0 - blackjack),
+        HoleCard::OriginalBetsOnly => value - blackjack,
+        HoleCard::NoHoleCard => value,
+    }
+}
+
+// Expected value of each play as a fraction of the initial bet. Plays the rules
+// or the hand don't allow are None
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecisionValues {
+    pub stand: f64,
+    pub hit: f64,
+    pub double: Option<f64>,
+    pub split: Option<f64>,
+    pub surrender: Option<f64>,
+}
+
+impl DecisionValues {
+    // The play worth the most and its expected value
+    #[inline(always)]
+    pub fn best(&self) -> (Decision, f64) {
+        let mut best = (Decision::Stand, self.stand);
+        let plays = [
+            (Decision::Hit, Some(self.hit)),
+            (Decision::Double, self.double),
+            (Decision::Split, self.split),
+            (Decision::Surrender, self.surrender),
+        ];
+        for (decision, value) in plays {
+            if let Some(value) = value {
+                if value > best.1 {
+                    best = (decision, value);
+                }
+            }
+        }
+        best
+    }
+}
+
+// Exact expected values of every play of the player's hand against the upcard.
+// The shoe holds every card the player hasn't seen, so the hand and upcard are
+// already out of it but the dealer's hole card is still in. Hitting plays on
+// optimally for the exact composition, and splits are valued by split_value().
+//
+// Under the peek rule the dealer is known not to have blackjack, otherwise a
+// dealer blackjack is settled by the hole card rule. Early surrender is decided
+// before the peek, so then every play is valued as EarlySurrenderLUT::generate
+// values it, with a dealer blackjack taking the original bet
+pub fn analyze(
+    rules: &GameRules,
+    shoe: &Composition,
+    hand: &HandCards,
+    upcard: Card,
+) -> DecisionValues {
+    let mut rules = rules.clone();
+    if rules.surrender == Some(Surrender::Early) && rules.hole_card == HoleCard::Peek {
+        rules.hole_card = HoleCard::OriginalBetsOnly;
+    }
+    let rules = &rules;
+    let upcard = rank_index(upcard.get_rank());
+    let state = HandState::from_cards(hand.cards());
+    let mut full_shoe = *shoe;
+    for rank in 0..10 {
+        full_shoe.counts[rank] += state.count(rank);
+        full_shoe.total += state.count(rank);
+    }
+    let mut evaluator = Evaluator::new(rules, full_shoe, upcard);
+    let two_cards = hand.len() == 2;
+    let can_double = rules.doubling.allows(hand.len(), state.total() as u8);
+    let pair_rank = rank_index(hand.first_card().get_rank());
+    let split = match hand.is_pair() {
+        true => Some(split_value(rules, full_shoe, upcard, pair_rank)),
+        false => None,
+    };
+    let stand = evaluator.stand(state);
+    let hit = evaluator.hit(state);
+    let double = can_double.then(|| evaluator.double(state));
+    DecisionValues {
+        stand: evaluator.settle_hole_card(&state, stand, 1.0),
+        hit: evaluator.settle_hole_card(&state, hit, 1.0),
+        double: double.map(|double| evaluator.settle_hole_card(&state, double, 2.0)),
+        split,
+        surrender: (two_cards && (rules.late_surrender() || rules.surrender == Some(Surrender::Early)))
+            .then_some(-0.5),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use smallvec::SmallVec;
+    use super::*;
+
+    fn cards(text: &str) -> SmallVec<[Card; 4]> {
+        parse_cards(text).unwrap()
+    }
+
+    fn six_deck_s17() -> GameRules {
+        GameRules::new(6, Penetration::Fraction(0.75), false, true, None, false, false, false)
+    }
+
+    // Player hand and upcard dealt off the top of a full shoe
+    fn analyze_deal(rules: &GameRules, hand: &str, upcard: &str) -> DecisionValues {
+        let hand_cards = cards(hand);
+        let upcard: Card = upcard.parse().unwrap();
+        let mut seen = hand_cards.clone();
+        seen.push(upcard);
+        let shoe = Composition::unseen(rules.decks_in_shoe, &seen);
+        let mut hand = HandCards::new();
+        for card in hand_cards {
+            hand.push(card);
+        }
+        analyze(rules, &shoe, &hand, upcard)
+    }
+
+    #[test]
+    fn sixteen_against_a_ten_matches_published_values() {
+        let values = analyze_deal(&six_deck_s17(), "Ts 6h", "Td");
+        assert!((values.stand + 0.541).abs() < 0.001, "{:?}", values);
+        assert!((values.hit + 0.535).abs() < 0.001, "{:?}", values);
+        assert_eq!(values.best().0, Decision::Hit);
+    }
+
+    #[test]
+    fn splitting_once_is_worth_two_hands_played_without_the_pair() {
+        // Without resplits the cards drawn to one hand don't change the other's
+        // value, here for 8,8 against a ten the dealer has peeked under
+        let mut rules = six_deck_s17();
+        rules.max_hands = Some(2);
+        let mut shoe = Composition::new(6);
+        shoe.remove(9);
+        let mut split_shoe = shoe;
+        split_shoe.remove(7);
+        let mut evaluator = Evaluator::new(&rules, split_shoe, 9);
+        let first = HandState::default().push(7);
+        let probabilities = evaluator.draw_probabilities(&evaluator.remaining(&first));
+        let mut hand_value = 0.0;
+        for (second, probability) in probabilities.into_iter().enumerate() {
+            let hand = first.push(second);
+            let mut value = evaluator.best(hand);
+            if rules.doubling.allows(2, hand.total() as u8) {
+                value = value.max(evaluator.double(hand));
+            }
+            hand_value += probability * value;
+        }
+        let split = split_value(&rules, shoe, 9, 7);
+        assert!((split - 2.0 * hand_value).abs() < 1e-9, "{} {}", split, hand_value);
+    }
+
+    #[test]
+    fn single_deck_h17_nines_split_against_an_ace_only_with_das() {
+        let mut rules = GameRules::new(1, Penetration::Fraction(0.75), true, true, None, false, false, false);
+        let das = analyze_deal(&rules, "9s 9h", "Ad");
+        assert_eq!(das.best().0, Decision::Split, "{:?}", das);
+        rules.double_after_split = false;
+        let no_das = analyze_deal(&rules, "9s 9h", "Ad");
+        assert_eq!(no_das.best().0, Decision::Stand, "{:?}", no_das);
+    }
+
+    #[test]
+    fn early_surrender_agrees_with_the_table() {
+        let mut rules = six_deck_s17();
+        rules.surrender = Some(Surrender::Early);
+        let table = EarlySurrenderLUT::for_rules(&rules);
+        // 15 against an ace and 14 against a ten are surrendered before the peek
+        for (hand, upcard, total, dealer_index) in [("Ts 5h", "Ad", 15, 9), ("Ts 4h", "Kd", 14, 8)] {
+            let values = analyze_deal(&rules, hand, upcard);
+            assert!(table.hard[total - 4][dealer_index]);
+            assert_eq!(values.best().0, Decision::Surrender, "{:?}", values);
+        }
+    }
+
+    #[test]
+    fn splitting_from_a_shoe_of_only_the_pair_rank_is_finite() {
+        let mut shoe = Composition::new(1);
+        shoe.counts = [0, 0, 0, 0, 0, 0, 0, 6, 0, 0];
+        shoe.total = 6;
+        let value = split_value(&six_deck_s17(), shoe, 5, 7);
+        assert!(value.is_finite());
+    }
+}
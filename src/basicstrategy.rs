@@ -1,4 +1,6 @@
 use crate::cardutils::*;
+use crate::counting::*;
+use crate::deviations::*;
 use crate::gamelogic::*;
 use Decision::*;
 
@@ -11,6 +13,11 @@ pub enum Decision {
     Split,
     DoubleOrStand,
     SplitIfDASOrHit,
+    SplitIfDASOrDouble,
+    SplitIfDASOrStand,
+    // Split if doubling after splitting is allowed, otherwise surrender, or split
+    // anyway when surrender isn't offered
+    SplitIfDASOrSurrender,
     SurrenderOrHit,
     SurrenderOrStand,
     SurrenderOrSplit,
@@ -28,18 +35,25 @@ pub enum Surrender {
     Late,
     Early,
 }
-#[derive(Debug, Clone)]
+// Hands to give up before the dealer checks for blackjack, when early surrender
+// is offered. Soft hands are never worth surrendering
+#[derive(Debug, Clone, PartialEq)]
+pub struct EarlySurrenderLUT {
+    pub hard: [[bool; 10]; 15],
+    pub pair: [[bool; 10]; 10],
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct BasicStrategyLUT {
-    hard: [[Decision; 10]; 15],
-    soft: [[Decision; 10]; 9],
-    pair: [[Decision; 10]; 10],
+    pub hard: [[Decision; 10]; 15],
+    pub soft: [[Decision; 10]; 9],
+    pub pair: [[Decision; 10]; 10],
 }
 
 impl BasicStrategyLUT {
-    // TODO deviations
     #[inline(always)]
-    pub fn make_move(game: &Game, hand_index: usize) -> Decision {
-        let raw_move = Self::raw_move(game, hand_index);
+    pub fn make_move<S: CountingSystem>(game: &Game<S>, hand_index: usize) -> Decision {
+        let raw_move = Self::deviated_move(game, hand_index);
         let can_double = game.current_round.can_double(hand_index);
         let can_hit = game.current_round.can_hit(hand_index);
         let can_surrender = game.current_round.can_surrender();
@@ -56,8 +70,10 @@ impl BasicStrategyLUT {
             Double => {
                 if can_double {
                     Double
-                } else {
+                } else if can_hit {
                     Hit
+                } else {
+                    Stand
                 }
             }
             DoubleOrStand => {
@@ -75,6 +91,31 @@ impl BasicStrategyLUT {
                     Hit
                 }
             }
+            SplitIfDASOrDouble => {
+                if double_after_split {
+                    Split
+                } else if can_double {
+                    Double
+                } else if can_hit {
+                    Hit
+                } else {
+                    Stand
+                }
+            }
+            SplitIfDASOrStand => {
+                if double_after_split {
+                    Split
+                } else {
+                    Stand
+                }
+            }
+            SplitIfDASOrSurrender => {
+                if !double_after_split && can_surrender {
+                    Surrender
+                } else {
+                    Split
+                }
+            }
             SurrenderOrHit => {
                 if can_surrender {
                     Surrender
@@ -96,12 +137,41 @@ impl BasicStrategyLUT {
                     Split
                 }
             }
-            Surrender => unreachable!(),
+            // Only index plays surrender outright, and only when surrender is available
+            Surrender => Surrender,
+        }
+    }
+
+    // The table's move, overridden by any index play that applies at the current true count
+    #[inline(always)]
+    pub fn deviated_move<S: CountingSystem>(game: &Game<S>, hand_index: usize) -> Decision {
+        let raw_move = Self::raw_move(game, hand_index);
+        let deviations = match &game.deviations {
+            Some(deviations) => deviations,
+            None => return raw_move,
+        };
+        let cards = &game.current_round.hands[hand_index].cards;
+        let hand = match Self::type_of_lut(game, hand_index) {
+            LUTTyupe::Hard => HandKey::Hard(cards.num_value()),
+            LUTTyupe::Soft => HandKey::Soft(cards.num_value()),
+            LUTTyupe::Pair => HandKey::Pair(cards.first_card().value()),
+        };
+        let dealer = game.current_round.dealer.first_card().value();
+        let can_surrender = game.current_round.can_surrender();
+        let table_surrenders = matches!(raw_move, SurrenderOrHit | SurrenderOrStand);
+        match deviations.lookup(hand, dealer, game.true_count(), can_surrender) {
+            // Hitting overrides the table's surrender too, like 15 against a ten
+            // below 0. Standing and splitting keep it and only replace what to do
+            // without it
+            Some(Stand) if table_surrenders => SurrenderOrStand,
+            Some(Split) if raw_move == SurrenderOrSplit => SurrenderOrSplit,
+            Some(deviation) => deviation,
+            None => raw_move,
         }
     }
 
     #[inline(always)]
-    pub fn calculate_cache(game: &Game) {
+    pub fn calculate_cache<S: CountingSystem>(game: &Game<S>) {
         Self::cache_lut(Self::which_lut(game));
     }
 
@@ -113,7 +183,7 @@ impl BasicStrategyLUT {
     }
 
     #[inline(always)]
-    pub fn raw_move(game: &Game, hand_index: usize) -> Decision {
+    pub fn raw_move<S: CountingSystem>(game: &Game<S>, hand_index: usize) -> Decision {
         let (lut, lut_type) = Self::get_lut(game, hand_index);
         let (player_index, dealer_index) = Self::get_indices(game, lut_type, hand_index);
 
@@ -125,7 +195,11 @@ impl BasicStrategyLUT {
     }
 
     #[inline(always)]
-    fn get_indices(game: &Game, lut_type: LUTTyupe, hand_index: usize) -> (usize, usize) {
+    fn get_indices<S: CountingSystem>(
+        game: &Game<S>,
+        lut_type: LUTTyupe,
+        hand_index: usize,
+    ) -> (usize, usize) {
         match lut_type {
             LUTTyupe::Hard => Self::get_hard_indices(game, hand_index),
             LUTTyupe::Soft => Self::get_soft_indices(game, hand_index),
@@ -134,7 +208,7 @@ impl BasicStrategyLUT {
     }
 
     #[inline(always)]
-    fn get_pair_indices(game: &Game, hand_index: usize) -> (usize, usize) {
+    fn get_pair_indices<S: CountingSystem>(game: &Game<S>, hand_index: usize) -> (usize, usize) {
         let pair_of = game.current_round.hands[hand_index]
             .cards
             .first_card()
@@ -151,7 +225,7 @@ impl BasicStrategyLUT {
     }
 
     #[inline(always)]
-    fn get_hard_indices(game: &Game, hand_index: usize) -> (usize, usize) {
+    fn get_hard_indices<S: CountingSystem>(game: &Game<S>, hand_index: usize) -> (usize, usize) {
         let value = game.current_round.hands[hand_index].cards.num_value();
         let dealer_index = Self::dealer_index(game);
         let player_index = match value {
@@ -162,7 +236,7 @@ impl BasicStrategyLUT {
     }
 
     #[inline(always)]
-    fn get_soft_indices(game: &Game, hand_index: usize) -> (usize, usize) {
+    fn get_soft_indices<S: CountingSystem>(game: &Game<S>, hand_index: usize) -> (usize, usize) {
         let value = game.current_round.hands[hand_index].cards.num_value();
         let dealer_index = Self::dealer_index(game);
         let player_index = match value {
@@ -173,13 +247,16 @@ impl BasicStrategyLUT {
     }
 
     #[inline(always)]
-    fn dealer_index(game: &Game) -> usize {
+    fn dealer_index<S: CountingSystem>(game: &Game<S>) -> usize {
         let dealer_card = game.current_round.dealer.first_card();
         (dealer_card.value() - 2) as usize
     }
 
     #[inline(always)]
-    fn get_lut(game: &Game, hand_index: usize) -> (&'static BasicStrategyLUT, LUTTyupe) {
+    fn get_lut<S: CountingSystem>(
+        game: &Game<S>,
+        hand_index: usize,
+    ) -> (&'static BasicStrategyLUT, LUTTyupe) {
         (Self::get_cached_lut(), Self::type_of_lut(game, hand_index))
     }
 
@@ -189,7 +266,7 @@ impl BasicStrategyLUT {
     }
 
     #[inline(always)]
-    fn type_of_lut(game: &Game, hand_index: usize) -> LUTTyupe {
+    fn type_of_lut<S: CountingSystem>(game: &Game<S>, hand_index: usize) -> LUTTyupe {
         let can_split = game.current_round.can_split(hand_index);
         let value_type = game.current_round.hands[hand_index].cards.value_type();
         if can_split {
@@ -202,31 +279,71 @@ impl BasicStrategyLUT {
     }
 
     #[inline(always)]
-    fn which_lut(game: &Game) -> &'static BasicStrategyLUT {
-        let hit_soft_17 = game.rules.hit_soft_17;
-        let decks_in_shoe = game.rules.decks_in_shoe;
+    fn which_lut<S: CountingSystem>(game: &Game<S>) -> &'static BasicStrategyLUT {
+        Self::for_rules(&game.rules)
+    }
+
+    // Whether to give up the first hand before the dealer checks for blackjack
+    #[inline(always)]
+    pub fn early_surrender<S: CountingSystem>(game: &Game<S>) -> bool {
+        let lut = EarlySurrenderLUT::for_rules(&game.rules);
+        let lut_type = Self::type_of_lut(game, 0);
+        let (player_index, dealer_index) = Self::get_indices(game, lut_type, 0);
+        match lut_type {
+            LUTTyupe::Hard => lut.hard[player_index][dealer_index],
+            LUTTyupe::Soft => false,
+            LUTTyupe::Pair => lut.pair[player_index][dealer_index],
+        }
+    }
+
+    // The hand-written table for the rules
+    #[inline(always)]
+    pub fn for_rules(rules: &GameRules) -> &'static BasicStrategyLUT {
+        let hit_soft_17 = rules.hit_soft_17;
+        let decks_in_shoe = rules.decks_in_shoe;
         match (hit_soft_17, decks_in_shoe) {
-            (false, 1) => unimplemented!(),
-            (false, 2) => unimplemented!(),
+            (false, 1) => &BS_ONE_DECK_S17,
+            (false, 2) => &BS_TWO_DECK_S17,
             (false, 4..=8) => &BS_FOUR_EIGHT_DECK_S17,
-            (true, 1) => unimplemented!(),
-            (true, 2) => unimplemented!(),
-            (true, 4..=8) => unimplemented!(),
+            (true, 1) => &BS_ONE_DECK_H17,
+            (true, 2) => &BS_TWO_DECK_H17,
+            (true, 4..=8) => &BS_FOUR_EIGHT_DECK_H17,
             _ => panic!("Invalid rules"),
         }
     }
 }
 
-// TODO more basic strategy LUTs for different rulesets
-static BS_FOUR_EIGHT_DECK_S17: BasicStrategyLUT = BasicStrategyLUT {
+impl EarlySurrenderLUT {
+    // The hand-written table for the rules. Doubling after splits only changes
+    // single deck 8,8 against a ten, which is split with it and surrendered without
+    #[inline(always)]
+    pub fn for_rules(rules: &GameRules) -> &'static EarlySurrenderLUT {
+        match (rules.hit_soft_17, rules.decks_in_shoe) {
+            (false, 1) if rules.double_after_split => &ES_ONE_DECK_S17_DAS,
+            (false, 1) => &ES_ONE_DECK_S17,
+            (false, 2) => &ES_TWO_DECK_S17,
+            (false, 4..=8) => &ES_FOUR_EIGHT_DECK_S17,
+            (true, 1) if rules.double_after_split => &ES_ONE_DECK_H17_DAS,
+            (true, 1) => &ES_ONE_DECK_H17,
+            (true, 2) => &ES_TWO_DECK_H17,
+            (true, 4..=8) => &ES_FOUR_EIGHT_DECK_H17,
+            _ => panic!("Invalid rules"),
+        }
+    }
+}
+
+#[rustfmt::skip]
+static BS_ONE_DECK_S17: BasicStrategyLUT = BasicStrategyLUT {
     hard: [
         // 2    3    4    5    6    7    8    9   10    A
         [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Four
         [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Five
         [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Six
         [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Seven
-        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Eight
-        [Hit, Double, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Nine
+        [Hit, Hit, Hit, Double, Double, Hit, Hit, Hit, Hit, Hit], // Eight
+        [
+            Double, Double, Double, Double, Double, Hit, Hit, Hit, Hit, Hit,
+        ], // Nine
         [
             Double, Double, Double, Double, Double, Double, Double, Double, Hit, Hit,
         ], // Ten
@@ -236,6 +353,7 @@ static BS_FOUR_EIGHT_DECK_S17: BasicStrategyLUT = BasicStrategyLUT {
         [Hit, Hit, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Twelve
         [Stand, Stand, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Thirteen
         [Stand, Stand, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Fourteen
+        [Stand, Stand, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Fifteen
         [
             Stand,
             Stand,
@@ -247,30 +365,179 @@ static BS_FOUR_EIGHT_DECK_S17: BasicStrategyLUT = BasicStrategyLUT {
             Hit,
             SurrenderOrHit,
             SurrenderOrHit,
-        ], // Fifteen
+        ], // Sixteen
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // Seventeen
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // Eighteen+
+    ],
+    soft: [
+        // 2    3    4    5       6    7    8    9   10    A
+        [Hit, Hit, Hit, Double, Double, Hit, Hit, Hit, Hit, Hit], // A,A , or twelve, used when you cant split aces
+        [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Thirteen
+        [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Fourteen
+        [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Fifteen
+        [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Sixteen
+        [
+            Double, Double, Double, Double, Double, Hit, Hit, Hit, Hit, Hit,
+        ], // Seventeen
+        [
+            Stand,
+            DoubleOrStand,
+            DoubleOrStand,
+            DoubleOrStand,
+            DoubleOrStand,
+            Stand,
+            Stand,
+            Hit,
+            Hit,
+            Stand,
+        ], // Eighteen
         [
             Stand,
             Stand,
             Stand,
             Stand,
+            DoubleOrStand,
+            Stand,
+            Stand,
+            Stand,
             Stand,
+            Stand,
+        ], // Nineteen
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // Twenty+
+    ],
+    pair: [
+        //             2                3      4      5      6      7    8    9   10    A
+        [
+            SplitIfDASOrHit,
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
             Hit,
             Hit,
-            SurrenderOrHit,
-            SurrenderOrHit,
-            SurrenderOrHit,
-        ], // Sixteen
+            Hit,
+            Hit,
+        ], // 2,2
+        [
+            SplitIfDASOrHit,
+            SplitIfDASOrHit,
+            Split,
+            Split,
+            Split,
+            Split,
+            SplitIfDASOrHit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 3,3
+        [
+            Hit,
+            Hit,
+            SplitIfDASOrHit,
+            SplitIfDASOrDouble,
+            SplitIfDASOrDouble,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 4,4
         [
+            Double, Double, Double, Double, Double, Double, Double, Double, Hit, Hit,
+        ], // 5,5
+        [
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            SplitIfDASOrHit,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 6,6
+        [
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            SplitIfDASOrHit,
+            Hit,
+            SurrenderOrStand,
+            Hit,
+        ], // 7,7
+        [
+            Split, Split, Split, Split, Split, Split, Split, Split, Split, Split,
+        ], // 8,8
+        [
+            Split, Split, Split, Split, Split, Stand, Split, Split, Stand, Stand,
+        ], // 9,9
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // 10,10
+        [
+            Split, Split, Split, Split, Split, Split, Split, Split, Split, Split,
+        ], // A,A
+    ],
+};
+
+#[rustfmt::skip]
+static BS_TWO_DECK_S17: BasicStrategyLUT = BasicStrategyLUT {
+    hard: [
+        // 2    3    4    5    6    7    8    9   10    A
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Four
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Five
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Six
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Seven
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Eight
+        [
+            Double, Double, Double, Double, Double, Hit, Hit, Hit, Hit, Hit,
+        ], // Nine
+        [
+            Double, Double, Double, Double, Double, Double, Double, Double, Hit, Hit,
+        ], // Ten
+        [
+            Double, Double, Double, Double, Double, Double, Double, Double, Double, Double,
+        ], // Eleven
+        [Hit, Hit, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Twelve
+        [Stand, Stand, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Thirteen
+        [Stand, Stand, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Fourteen
+        [
+            Stand,
             Stand,
             Stand,
             Stand,
             Stand,
+            Hit,
+            Hit,
+            Hit,
+            SurrenderOrHit,
+            Hit,
+        ], // Fifteen
+        [
             Stand,
             Stand,
             Stand,
             Stand,
             Stand,
-            SurrenderOrStand,
+            Hit,
+            Hit,
+            Hit,
+            SurrenderOrHit,
+            SurrenderOrHit,
+        ], // Sixteen
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
         ], // Seventeen
         [
             Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
@@ -278,14 +545,14 @@ static BS_FOUR_EIGHT_DECK_S17: BasicStrategyLUT = BasicStrategyLUT {
     ],
     soft: [
         // 2    3    4    5       6    7    8    9   10    A
-        [Hit, Hit, Hit, Hit, Double, Hit, Hit, Hit, Hit, Hit], // A,A , or twelve, used when you cant split aces
+        [Hit, Hit, Hit, Double, Double, Hit, Hit, Hit, Hit, Hit], // A,A , or twelve, used when you cant split aces
         [Hit, Hit, Hit, Double, Double, Hit, Hit, Hit, Hit, Hit], // Thirteen
         [Hit, Hit, Hit, Double, Double, Hit, Hit, Hit, Hit, Hit], // Fourteen
         [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Fifteen
         [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Sixteen
         [Hit, Double, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Seventeen
         [
-            DoubleOrStand,
+            Stand,
             DoubleOrStand,
             DoubleOrStand,
             DoubleOrStand,
@@ -297,16 +564,7 @@ static BS_FOUR_EIGHT_DECK_S17: BasicStrategyLUT = BasicStrategyLUT {
             Hit,
         ], // Eighteen
         [
-            Stand,
-            Stand,
-            Stand,
-            Stand,
-            DoubleOrStand,
-            Stand,
-            Stand,
-            Stand,
-            Stand,
-            Stand,
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
         ], // Nineteen
         [
             Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
@@ -351,44 +609,37 @@ static BS_FOUR_EIGHT_DECK_S17: BasicStrategyLUT = BasicStrategyLUT {
             Hit,
         ], // 4,4
         [
-            SplitIfDASOrHit,
+            Double, Double, Double, Double, Double, Double, Double, Double, Hit, Hit,
+        ], // 5,5
+        [
             Split,
             Split,
             Split,
             Split,
-            Hit,
+            Split,
+            SplitIfDASOrHit,
             Hit,
             Hit,
             Hit,
             Hit,
         ], // 6,6
         [
-            Double, Double, Double, Double, Double, Double, Double, Double, Hit, Hit,
-        ], // 5,5
-        [Split, Split, Split, Split, Split, Split, Hit, Hit, Hit, Hit], // 7,7
-        [
-            Split,
             Split,
             Split,
             Split,
             Split,
             Split,
             Split,
-            Split,
-            Split,
-            SurrenderOrSplit,
+            SplitIfDASOrHit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 7,7
+        [
+            Split, Split, Split, Split, Split, Split, Split, Split, Split, Split,
         ], // 8,8
         [
-            Split,
-            Split,
-            Split,
-            Split,
-            Split,
-            Split,
-            Split,
-            Split,
-            Split,
-            SurrenderOrSplit,
+            Split, Split, Split, Split, Split, Stand, Split, Split, Stand, Stand,
         ], // 9,9
         [
             Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
@@ -399,4 +650,967 @@ static BS_FOUR_EIGHT_DECK_S17: BasicStrategyLUT = BasicStrategyLUT {
     ],
 };
 
+#[rustfmt::skip]
+static BS_FOUR_EIGHT_DECK_S17: BasicStrategyLUT = BasicStrategyLUT {
+    hard: [
+        // 2    3    4    5    6    7    8    9   10    A
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Four
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Five
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Six
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Seven
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Eight
+        [Hit, Double, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Nine
+        [
+            Double, Double, Double, Double, Double, Double, Double, Double, Hit, Hit,
+        ], // Ten
+        [
+            Double, Double, Double, Double, Double, Double, Double, Double, Double, Hit,
+        ], // Eleven
+        [Hit, Hit, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Twelve
+        [Stand, Stand, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Thirteen
+        [Stand, Stand, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Fourteen
+        [
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Hit,
+            Hit,
+            Hit,
+            SurrenderOrHit,
+            Hit,
+        ], // Fifteen
+        [
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Hit,
+            Hit,
+            SurrenderOrHit,
+            SurrenderOrHit,
+            SurrenderOrHit,
+        ], // Sixteen
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // Seventeen
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // Eighteen+
+    ],
+    soft: [
+        // 2    3    4    5       6    7    8    9   10    A
+        [Hit, Hit, Hit, Hit, Double, Hit, Hit, Hit, Hit, Hit], // A,A , or twelve, used when you cant split aces
+        [Hit, Hit, Hit, Double, Double, Hit, Hit, Hit, Hit, Hit], // Thirteen
+        [Hit, Hit, Hit, Double, Double, Hit, Hit, Hit, Hit, Hit], // Fourteen
+        [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Fifteen
+        [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Sixteen
+        [Hit, Double, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Seventeen
+        [
+            Stand,
+            DoubleOrStand,
+            DoubleOrStand,
+            DoubleOrStand,
+            DoubleOrStand,
+            Stand,
+            Stand,
+            Hit,
+            Hit,
+            Hit,
+        ], // Eighteen
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // Nineteen
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // Twenty+
+    ],
+    pair: [
+        //             2                3      4      5      6      7    8    9   10    A
+        [
+            SplitIfDASOrHit,
+            SplitIfDASOrHit,
+            Split,
+            Split,
+            Split,
+            Split,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 2,2
+        [
+            SplitIfDASOrHit,
+            SplitIfDASOrHit,
+            Split,
+            Split,
+            Split,
+            Split,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 3,3
+        [
+            Hit,
+            Hit,
+            Hit,
+            SplitIfDASOrHit,
+            SplitIfDASOrHit,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 4,4
+        [
+            Double, Double, Double, Double, Double, Double, Double, Double, Hit, Hit,
+        ], // 5,5
+        [
+            SplitIfDASOrHit,
+            Split,
+            Split,
+            Split,
+            Split,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 6,6
+        [Split, Split, Split, Split, Split, Split, Hit, Hit, Hit, Hit], // 7,7
+        [
+            Split, Split, Split, Split, Split, Split, Split, Split, Split, Split,
+        ], // 8,8
+        [
+            Split, Split, Split, Split, Split, Stand, Split, Split, Stand, Stand,
+        ], // 9,9
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // 10,10
+        [
+            Split, Split, Split, Split, Split, Split, Split, Split, Split, Split,
+        ], // A,A
+    ],
+};
+
+#[rustfmt::skip]
+static BS_ONE_DECK_H17: BasicStrategyLUT = BasicStrategyLUT {
+    hard: [
+        // 2    3    4    5    6    7    8    9   10    A
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Four
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Five
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Six
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Seven
+        [Hit, Hit, Hit, Double, Double, Hit, Hit, Hit, Hit, Hit], // Eight
+        [
+            Double, Double, Double, Double, Double, Hit, Hit, Hit, Hit, Hit,
+        ], // Nine
+        [
+            Double, Double, Double, Double, Double, Double, Double, Double, Hit, Hit,
+        ], // Ten
+        [
+            Double, Double, Double, Double, Double, Double, Double, Double, Double, Double,
+        ], // Eleven
+        [Hit, Hit, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Twelve
+        [Stand, Stand, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Thirteen
+        [Stand, Stand, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Fourteen
+        [
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+            SurrenderOrHit,
+        ], // Fifteen
+        [
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Hit,
+            Hit,
+            Hit,
+            SurrenderOrHit,
+            SurrenderOrHit,
+        ], // Sixteen
+        [
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            SurrenderOrStand,
+        ], // Seventeen
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // Eighteen+
+    ],
+    soft: [
+        // 2    3    4    5       6    7    8    9   10    A
+        [Hit, Hit, Hit, Double, Double, Hit, Hit, Hit, Hit, Hit], // A,A , or twelve, used when you cant split aces
+        [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Thirteen
+        [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Fourteen
+        [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Fifteen
+        [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Sixteen
+        [
+            Double, Double, Double, Double, Double, Hit, Hit, Hit, Hit, Hit,
+        ], // Seventeen
+        [
+            Stand,
+            DoubleOrStand,
+            DoubleOrStand,
+            DoubleOrStand,
+            DoubleOrStand,
+            Stand,
+            Stand,
+            Hit,
+            Hit,
+            Hit,
+        ], // Eighteen
+        [
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            DoubleOrStand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+        ], // Nineteen
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // Twenty+
+    ],
+    pair: [
+        //             2                3      4      5      6      7    8    9   10    A
+        [
+            SplitIfDASOrHit,
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 2,2
+        [
+            SplitIfDASOrHit,
+            SplitIfDASOrHit,
+            Split,
+            Split,
+            Split,
+            Split,
+            SplitIfDASOrHit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 3,3
+        [
+            Hit,
+            Hit,
+            SplitIfDASOrHit,
+            SplitIfDASOrDouble,
+            SplitIfDASOrDouble,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 4,4
+        [
+            Double, Double, Double, Double, Double, Double, Double, Double, Hit, Hit,
+        ], // 5,5
+        [
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            SplitIfDASOrHit,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 6,6
+        [
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            SplitIfDASOrHit,
+            Hit,
+            SurrenderOrStand,
+            SurrenderOrHit,
+        ], // 7,7
+        [
+            Split, Split, Split, Split, Split, Split, Split, Split, Split, Split,
+        ], // 8,8
+        [
+            Split, Split, Split, Split, Split, Stand, Split, Split, Stand, SplitIfDASOrStand,
+        ], // 9,9
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // 10,10
+        [
+            Split, Split, Split, Split, Split, Split, Split, Split, Split, Split,
+        ], // A,A
+    ],
+};
+
+#[rustfmt::skip]
+static BS_TWO_DECK_H17: BasicStrategyLUT = BasicStrategyLUT {
+    hard: [
+        // 2    3    4    5    6    7    8    9   10    A
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Four
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Five
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Six
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Seven
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Eight
+        [
+            Double, Double, Double, Double, Double, Hit, Hit, Hit, Hit, Hit,
+        ], // Nine
+        [
+            Double, Double, Double, Double, Double, Double, Double, Double, Hit, Hit,
+        ], // Ten
+        [
+            Double, Double, Double, Double, Double, Double, Double, Double, Double, Double,
+        ], // Eleven
+        [Hit, Hit, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Twelve
+        [Stand, Stand, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Thirteen
+        [Stand, Stand, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Fourteen
+        [
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Hit,
+            Hit,
+            Hit,
+            SurrenderOrHit,
+            SurrenderOrHit,
+        ], // Fifteen
+        [
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Hit,
+            Hit,
+            Hit,
+            SurrenderOrHit,
+            SurrenderOrHit,
+        ], // Sixteen
+        [
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            SurrenderOrStand,
+        ], // Seventeen
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // Eighteen+
+    ],
+    soft: [
+        // 2    3    4    5       6    7    8    9   10    A
+        [Hit, Hit, Hit, Double, Double, Hit, Hit, Hit, Hit, Hit], // A,A , or twelve, used when you cant split aces
+        [Hit, Hit, Hit, Double, Double, Hit, Hit, Hit, Hit, Hit], // Thirteen
+        [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Fourteen
+        [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Fifteen
+        [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Sixteen
+        [Hit, Double, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Seventeen
+        [
+            DoubleOrStand,
+            DoubleOrStand,
+            DoubleOrStand,
+            DoubleOrStand,
+            DoubleOrStand,
+            Stand,
+            Stand,
+            Hit,
+            Hit,
+            Hit,
+        ], // Eighteen
+        [
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            DoubleOrStand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+        ], // Nineteen
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // Twenty+
+    ],
+    pair: [
+        //             2                3      4      5      6      7    8    9   10    A
+        [
+            SplitIfDASOrHit,
+            SplitIfDASOrHit,
+            Split,
+            Split,
+            Split,
+            Split,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 2,2
+        [
+            SplitIfDASOrHit,
+            SplitIfDASOrHit,
+            Split,
+            Split,
+            Split,
+            Split,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 3,3
+        [
+            Hit,
+            Hit,
+            Hit,
+            SplitIfDASOrHit,
+            SplitIfDASOrHit,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 4,4
+        [
+            Double, Double, Double, Double, Double, Double, Double, Double, Hit, Hit,
+        ], // 5,5
+        [
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            SplitIfDASOrHit,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 6,6
+        [
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            SplitIfDASOrHit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 7,7
+        [
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            SplitIfDASOrSurrender,
+        ], // 8,8
+        [
+            Split, Split, Split, Split, Split, Stand, Split, Split, Stand, Stand,
+        ], // 9,9
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // 10,10
+        [
+            Split, Split, Split, Split, Split, Split, Split, Split, Split, Split,
+        ], // A,A
+    ],
+};
+
+#[rustfmt::skip]
+static BS_FOUR_EIGHT_DECK_H17: BasicStrategyLUT = BasicStrategyLUT {
+    hard: [
+        // 2    3    4    5    6    7    8    9   10    A
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Four
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Five
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Six
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Seven
+        [Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit, Hit], // Eight
+        [Hit, Double, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Nine
+        [
+            Double, Double, Double, Double, Double, Double, Double, Double, Hit, Hit,
+        ], // Ten
+        [
+            Double, Double, Double, Double, Double, Double, Double, Double, Double, Double,
+        ], // Eleven
+        [Hit, Hit, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Twelve
+        [Stand, Stand, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Thirteen
+        [Stand, Stand, Stand, Stand, Stand, Hit, Hit, Hit, Hit, Hit], // Fourteen
+        [
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Hit,
+            Hit,
+            Hit,
+            SurrenderOrHit,
+            SurrenderOrHit,
+        ], // Fifteen
+        [
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Hit,
+            Hit,
+            SurrenderOrHit,
+            SurrenderOrHit,
+            SurrenderOrHit,
+        ], // Sixteen
+        [
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            SurrenderOrStand,
+        ], // Seventeen
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // Eighteen+
+    ],
+    soft: [
+        // 2    3    4    5       6    7    8    9   10    A
+        [Hit, Hit, Hit, Hit, Double, Hit, Hit, Hit, Hit, Hit], // A,A , or twelve, used when you cant split aces
+        [Hit, Hit, Hit, Double, Double, Hit, Hit, Hit, Hit, Hit], // Thirteen
+        [Hit, Hit, Hit, Double, Double, Hit, Hit, Hit, Hit, Hit], // Fourteen
+        [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Fifteen
+        [Hit, Hit, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Sixteen
+        [Hit, Double, Double, Double, Double, Hit, Hit, Hit, Hit, Hit], // Seventeen
+        [
+            DoubleOrStand,
+            DoubleOrStand,
+            DoubleOrStand,
+            DoubleOrStand,
+            DoubleOrStand,
+            Stand,
+            Stand,
+            Hit,
+            Hit,
+            Hit,
+        ], // Eighteen
+        [
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            DoubleOrStand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+            Stand,
+        ], // Nineteen
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // Twenty+
+    ],
+    pair: [
+        //             2                3      4      5      6      7    8    9   10    A
+        [
+            SplitIfDASOrHit,
+            SplitIfDASOrHit,
+            Split,
+            Split,
+            Split,
+            Split,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 2,2
+        [
+            SplitIfDASOrHit,
+            SplitIfDASOrHit,
+            Split,
+            Split,
+            Split,
+            Split,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 3,3
+        [
+            Hit,
+            Hit,
+            Hit,
+            SplitIfDASOrHit,
+            SplitIfDASOrHit,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 4,4
+        [
+            Double, Double, Double, Double, Double, Double, Double, Double, Hit, Hit,
+        ], // 5,5
+        [
+            SplitIfDASOrHit,
+            Split,
+            Split,
+            Split,
+            Split,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+            Hit,
+        ], // 6,6
+        [Split, Split, Split, Split, Split, Split, Hit, Hit, Hit, Hit], // 7,7
+        [
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            Split,
+            SurrenderOrSplit,
+        ], // 8,8
+        [
+            Split, Split, Split, Split, Split, Stand, Split, Split, Stand, Stand,
+        ], // 9,9
+        [
+            Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand, Stand,
+        ], // 10,10
+        [
+            Split, Split, Split, Split, Split, Split, Split, Split, Split, Split,
+        ], // A,A
+    ],
+};
+
+#[rustfmt::skip]
+static ES_ONE_DECK_S17: EarlySurrenderLUT = EarlySurrenderLUT {
+    hard: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, false], // Four
+        [false, false, false, false, false, false, false, false, false, true ], // Five
+        [false, false, false, false, false, false, false, false, false, true ], // Six
+        [false, false, false, false, false, false, false, false, false, true ], // Seven
+        [false, false, false, false, false, false, false, false, false, false], // Eight
+        [false, false, false, false, false, false, false, false, false, false], // Nine
+        [false, false, false, false, false, false, false, false, false, false], // Ten
+        [false, false, false, false, false, false, false, false, false, false], // Eleven
+        [false, false, false, false, false, false, false, false, false, true ], // Twelve
+        [false, false, false, false, false, false, false, false, false, true ], // Thirteen
+        [false, false, false, false, false, false, false, false, false, true ], // Fourteen
+        [false, false, false, false, false, false, false, false, true , true ], // Fifteen
+        [false, false, false, false, false, false, false, false, true , true ], // Sixteen
+        [false, false, false, false, false, false, false, false, false, true ], // Seventeen
+        [false, false, false, false, false, false, false, false, false, false], // Eighteen+
+    ],
+    pair: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, true ], // Twos
+        [false, false, false, false, false, false, false, false, false, true ], // Threes
+        [false, false, false, false, false, false, false, false, false, false], // Fours
+        [false, false, false, false, false, false, false, false, false, false], // Fives
+        [false, false, false, false, false, false, false, false, false, true ], // Sixes
+        [false, false, false, false, false, false, false, false, true , true ], // Sevens
+        [false, false, false, false, false, false, false, false, true , true ], // Eights
+        [false, false, false, false, false, false, false, false, false, false], // Nines
+        [false, false, false, false, false, false, false, false, false, false], // Tens
+        [false, false, false, false, false, false, false, false, false, false], // Aces
+    ],
+};
+
+#[rustfmt::skip]
+static ES_ONE_DECK_S17_DAS: EarlySurrenderLUT = EarlySurrenderLUT {
+    hard: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, false], // Four
+        [false, false, false, false, false, false, false, false, false, true ], // Five
+        [false, false, false, false, false, false, false, false, false, true ], // Six
+        [false, false, false, false, false, false, false, false, false, true ], // Seven
+        [false, false, false, false, false, false, false, false, false, false], // Eight
+        [false, false, false, false, false, false, false, false, false, false], // Nine
+        [false, false, false, false, false, false, false, false, false, false], // Ten
+        [false, false, false, false, false, false, false, false, false, false], // Eleven
+        [false, false, false, false, false, false, false, false, false, true ], // Twelve
+        [false, false, false, false, false, false, false, false, false, true ], // Thirteen
+        [false, false, false, false, false, false, false, false, false, true ], // Fourteen
+        [false, false, false, false, false, false, false, false, true , true ], // Fifteen
+        [false, false, false, false, false, false, false, false, true , true ], // Sixteen
+        [false, false, false, false, false, false, false, false, false, true ], // Seventeen
+        [false, false, false, false, false, false, false, false, false, false], // Eighteen+
+    ],
+    pair: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, true ], // Twos
+        [false, false, false, false, false, false, false, false, false, true ], // Threes
+        [false, false, false, false, false, false, false, false, false, false], // Fours
+        [false, false, false, false, false, false, false, false, false, false], // Fives
+        [false, false, false, false, false, false, false, false, false, true ], // Sixes
+        [false, false, false, false, false, false, false, false, true , true ], // Sevens
+        [false, false, false, false, false, false, false, false, false, true ], // Eights
+        [false, false, false, false, false, false, false, false, false, false], // Nines
+        [false, false, false, false, false, false, false, false, false, false], // Tens
+        [false, false, false, false, false, false, false, false, false, false], // Aces
+    ],
+};
+
+#[rustfmt::skip]
+static ES_TWO_DECK_S17: EarlySurrenderLUT = EarlySurrenderLUT {
+    hard: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, false], // Four
+        [false, false, false, false, false, false, false, false, false, true ], // Five
+        [false, false, false, false, false, false, false, false, false, true ], // Six
+        [false, false, false, false, false, false, false, false, false, true ], // Seven
+        [false, false, false, false, false, false, false, false, false, false], // Eight
+        [false, false, false, false, false, false, false, false, false, false], // Nine
+        [false, false, false, false, false, false, false, false, false, false], // Ten
+        [false, false, false, false, false, false, false, false, false, false], // Eleven
+        [false, false, false, false, false, false, false, false, false, true ], // Twelve
+        [false, false, false, false, false, false, false, false, false, true ], // Thirteen
+        [false, false, false, false, false, false, false, false, true , true ], // Fourteen
+        [false, false, false, false, false, false, false, false, true , true ], // Fifteen
+        [false, false, false, false, false, false, false, false, true , true ], // Sixteen
+        [false, false, false, false, false, false, false, false, false, true ], // Seventeen
+        [false, false, false, false, false, false, false, false, false, false], // Eighteen+
+    ],
+    pair: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, false], // Twos
+        [false, false, false, false, false, false, false, false, false, true ], // Threes
+        [false, false, false, false, false, false, false, false, false, false], // Fours
+        [false, false, false, false, false, false, false, false, false, false], // Fives
+        [false, false, false, false, false, false, false, false, false, true ], // Sixes
+        [false, false, false, false, false, false, false, false, true , true ], // Sevens
+        [false, false, false, false, false, false, false, false, true , true ], // Eights
+        [false, false, false, false, false, false, false, false, false, false], // Nines
+        [false, false, false, false, false, false, false, false, false, false], // Tens
+        [false, false, false, false, false, false, false, false, false, false], // Aces
+    ],
+};
+
+#[rustfmt::skip]
+static ES_FOUR_EIGHT_DECK_S17: EarlySurrenderLUT = EarlySurrenderLUT {
+    hard: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, false], // Four
+        [false, false, false, false, false, false, false, false, false, true ], // Five
+        [false, false, false, false, false, false, false, false, false, true ], // Six
+        [false, false, false, false, false, false, false, false, false, true ], // Seven
+        [false, false, false, false, false, false, false, false, false, false], // Eight
+        [false, false, false, false, false, false, false, false, false, false], // Nine
+        [false, false, false, false, false, false, false, false, false, false], // Ten
+        [false, false, false, false, false, false, false, false, false, false], // Eleven
+        [false, false, false, false, false, false, false, false, false, true ], // Twelve
+        [false, false, false, false, false, false, false, false, false, true ], // Thirteen
+        [false, false, false, false, false, false, false, false, true , true ], // Fourteen
+        [false, false, false, false, false, false, false, false, true , true ], // Fifteen
+        [false, false, false, false, false, false, false, true , true , true ], // Sixteen
+        [false, false, false, false, false, false, false, false, false, true ], // Seventeen
+        [false, false, false, false, false, false, false, false, false, false], // Eighteen+
+    ],
+    pair: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, false], // Twos
+        [false, false, false, false, false, false, false, false, false, true ], // Threes
+        [false, false, false, false, false, false, false, false, false, false], // Fours
+        [false, false, false, false, false, false, false, false, false, false], // Fives
+        [false, false, false, false, false, false, false, false, false, true ], // Sixes
+        [false, false, false, false, false, false, false, false, true , true ], // Sevens
+        [false, false, false, false, false, false, false, false, true , true ], // Eights
+        [false, false, false, false, false, false, false, false, false, false], // Nines
+        [false, false, false, false, false, false, false, false, false, false], // Tens
+        [false, false, false, false, false, false, false, false, false, false], // Aces
+    ],
+};
+
+#[rustfmt::skip]
+static ES_ONE_DECK_H17: EarlySurrenderLUT = EarlySurrenderLUT {
+    hard: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, false], // Four
+        [false, false, false, false, false, false, false, false, false, true ], // Five
+        [false, false, false, false, false, false, false, false, false, true ], // Six
+        [false, false, false, false, false, false, false, false, false, true ], // Seven
+        [false, false, false, false, false, false, false, false, false, true ], // Eight
+        [false, false, false, false, false, false, false, false, false, false], // Nine
+        [false, false, false, false, false, false, false, false, false, false], // Ten
+        [false, false, false, false, false, false, false, false, false, false], // Eleven
+        [false, false, false, false, false, false, false, false, false, true ], // Twelve
+        [false, false, false, false, false, false, false, false, false, true ], // Thirteen
+        [false, false, false, false, false, false, false, false, false, true ], // Fourteen
+        [false, false, false, false, false, false, false, false, true , true ], // Fifteen
+        [false, false, false, false, false, false, false, false, true , true ], // Sixteen
+        [false, false, false, false, false, false, false, false, false, true ], // Seventeen
+        [false, false, false, false, false, false, false, false, false, false], // Eighteen+
+    ],
+    pair: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, true ], // Twos
+        [false, false, false, false, false, false, false, false, false, true ], // Threes
+        [false, false, false, false, false, false, false, false, false, true ], // Fours
+        [false, false, false, false, false, false, false, false, false, false], // Fives
+        [false, false, false, false, false, false, false, false, false, true ], // Sixes
+        [false, false, false, false, false, false, false, false, true , true ], // Sevens
+        [false, false, false, false, false, false, false, false, true , true ], // Eights
+        [false, false, false, false, false, false, false, false, false, false], // Nines
+        [false, false, false, false, false, false, false, false, false, false], // Tens
+        [false, false, false, false, false, false, false, false, false, false], // Aces
+    ],
+};
+
+#[rustfmt::skip]
+static ES_ONE_DECK_H17_DAS: EarlySurrenderLUT = EarlySurrenderLUT {
+    hard: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, false], // Four
+        [false, false, false, false, false, false, false, false, false, true ], // Five
+        [false, false, false, false, false, false, false, false, false, true ], // Six
+        [false, false, false, false, false, false, false, false, false, true ], // Seven
+        [false, false, false, false, false, false, false, false, false, true ], // Eight
+        [false, false, false, false, false, false, false, false, false, false], // Nine
+        [false, false, false, false, false, false, false, false, false, false], // Ten
+        [false, false, false, false, false, false, false, false, false, false], // Eleven
+        [false, false, false, false, false, false, false, false, false, true ], // Twelve
+        [false, false, false, false, false, false, false, false, false, true ], // Thirteen
+        [false, false, false, false, false, false, false, false, false, true ], // Fourteen
+        [false, false, false, false, false, false, false, false, true , true ], // Fifteen
+        [false, false, false, false, false, false, false, false, true , true ], // Sixteen
+        [false, false, false, false, false, false, false, false, false, true ], // Seventeen
+        [false, false, false, false, false, false, false, false, false, false], // Eighteen+
+    ],
+    pair: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, true ], // Twos
+        [false, false, false, false, false, false, false, false, false, true ], // Threes
+        [false, false, false, false, false, false, false, false, false, true ], // Fours
+        [false, false, false, false, false, false, false, false, false, false], // Fives
+        [false, false, false, false, false, false, false, false, false, true ], // Sixes
+        [false, false, false, false, false, false, false, false, true , true ], // Sevens
+        [false, false, false, false, false, false, false, false, false, true ], // Eights
+        [false, false, false, false, false, false, false, false, false, false], // Nines
+        [false, false, false, false, false, false, false, false, false, false], // Tens
+        [false, false, false, false, false, false, false, false, false, false], // Aces
+    ],
+};
+
+#[rustfmt::skip]
+static ES_TWO_DECK_H17: EarlySurrenderLUT = EarlySurrenderLUT {
+    hard: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, false], // Four
+        [false, false, false, false, false, false, false, false, false, true ], // Five
+        [false, false, false, false, false, false, false, false, false, true ], // Six
+        [false, false, false, false, false, false, false, false, false, true ], // Seven
+        [false, false, false, false, false, false, false, false, false, true ], // Eight
+        [false, false, false, false, false, false, false, false, false, false], // Nine
+        [false, false, false, false, false, false, false, false, false, false], // Ten
+        [false, false, false, false, false, false, false, false, false, false], // Eleven
+        [false, false, false, false, false, false, false, false, false, true ], // Twelve
+        [false, false, false, false, false, false, false, false, false, true ], // Thirteen
+        [false, false, false, false, false, false, false, false, true , true ], // Fourteen
+        [false, false, false, false, false, false, false, false, true , true ], // Fifteen
+        [false, false, false, false, false, false, false, false, true , true ], // Sixteen
+        [false, false, false, false, false, false, false, false, false, true ], // Seventeen
+        [false, false, false, false, false, false, false, false, false, false], // Eighteen+
+    ],
+    pair: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, true ], // Twos
+        [false, false, false, false, false, false, false, false, false, true ], // Threes
+        [false, false, false, false, false, false, false, false, false, false], // Fours
+        [false, false, false, false, false, false, false, false, false, false], // Fives
+        [false, false, false, false, false, false, false, false, false, true ], // Sixes
+        [false, false, false, false, false, false, false, false, true , true ], // Sevens
+        [false, false, false, false, false, false, false, false, true , true ], // Eights
+        [false, false, false, false, false, false, false, false, false, false], // Nines
+        [false, false, false, false, false, false, false, false, false, false], // Tens
+        [false, false, false, false, false, false, false, false, false, false], // Aces
+    ],
+};
+
+#[rustfmt::skip]
+static ES_FOUR_EIGHT_DECK_H17: EarlySurrenderLUT = EarlySurrenderLUT {
+    hard: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, false], // Four
+        [false, false, false, false, false, false, false, false, false, true ], // Five
+        [false, false, false, false, false, false, false, false, false, true ], // Six
+        [false, false, false, false, false, false, false, false, false, true ], // Seven
+        [false, false, false, false, false, false, false, false, false, false], // Eight
+        [false, false, false, false, false, false, false, false, false, false], // Nine
+        [false, false, false, false, false, false, false, false, false, false], // Ten
+        [false, false, false, false, false, false, false, false, false, false], // Eleven
+        [false, false, false, false, false, false, false, false, false, true ], // Twelve
+        [false, false, false, false, false, false, false, false, false, true ], // Thirteen
+        [false, false, false, false, false, false, false, false, true , true ], // Fourteen
+        [false, false, false, false, false, false, false, false, true , true ], // Fifteen
+        [false, false, false, false, false, false, false, true , true , true ], // Sixteen
+        [false, false, false, false, false, false, false, false, false, true ], // Seventeen
+        [false, false, false, false, false, false, false, false, false, false], // Eighteen+
+    ],
+    pair: [
+        //  2      3      4      5      6      7      8      9      10     A
+        [false, false, false, false, false, false, false, false, false, true ], // Twos
+        [false, false, false, false, false, false, false, false, false, true ], // Threes
+        [false, false, false, false, false, false, false, false, false, false], // Fours
+        [false, false, false, false, false, false, false, false, false, false], // Fives
+        [false, false, false, false, false, false, false, false, false, true ], // Sixes
+        [false, false, false, false, false, false, false, false, true , true ], // Sevens
+        [false, false, false, false, false, false, false, false, true , true ], // Eights
+        [false, false, false, false, false, false, false, false, false, false], // Nines
+        [false, false, false, false, false, false, false, false, false, false], // Tens
+        [false, false, false, false, false, false, false, false, false, false], // Aces
+    ],
+};
 static mut CACHED_LUT: &BasicStrategyLUT = &BS_FOUR_EIGHT_DECK_S17;
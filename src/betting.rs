@@ -0,0 +1,100 @@
+use smallvec::SmallVec;
+use crate::money::*;
+
+// Bets a number of units chosen from a ramp keyed on the true count, clamped to
+// the table limits
+#[derive(Debug, Clone)]
+pub struct BettingStrategy {
+    pub unit: Money,
+    pub table_min: Money,
+    pub table_max: Money,
+    // (true count, units) sorted by true count. The entry with the highest count
+    // at or below the current true count applies; counts below the first entry
+    // bet the first entry's units
+    pub ramp: SmallVec<[(isize, i64); 8]>,
+}
+
+impl BettingStrategy {
+    #[inline(always)]
+    pub fn new(unit: Money, table_min: Money, table_max: Money, ramp: &[(isize, i64)]) -> Self {
+        assert!(table_min <= table_max, "Table minimum is above the table maximum");
+        let mut ramp: SmallVec<[(isize, i64); 8]> = SmallVec::from_slice(ramp);
+        ramp.sort_by_key(|&(true_count, _)| true_count);
+        BettingStrategy {
+            unit,
+            table_min,
+            table_max,
+            ramp,
+        }
+    }
+
+    #[inline(always)]
+    pub fn flat(bet: Money) -> Self {
+        Self::new(bet, bet, bet, &[(0, 1)])
+    }
+
+    #[inline(always)]
+    pub fn units(&self, true_count: isize) -> i64 {
+        let mut units = match self.ramp.first() {
+            Some(&(_, units)) => units,
+            None => 1,
+        };
+        for &(threshold, ramp_units) in self.ramp.iter() {
+            if threshold > true_count {
+                break;
+            }
+            units = ramp_units;
+        }
+        units
+    }
+
+    #[inline(always)]
+    pub fn bet(&self, true_count: isize) -> Money {
+        (self.unit * self.units(true_count)).clamp(self.table_min, self.table_max)
+    }
+}
+
+impl Default for BettingStrategy {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::flat(Money::from_dollars(100))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // $25 units on a $25-$500 table, ramping from 2 units up to 12
+    fn ramp() -> BettingStrategy {
+        let (unit, table_max) = (Money::from_dollars(25), Money::from_dollars(500));
+        BettingStrategy::new(unit, unit, table_max, &[(1, 2), (2, 4), (4, 8), (6, 12)])
+    }
+
+    #[test]
+    fn counts_below_the_first_step_bet_its_units() {
+        assert_eq!(ramp().units(-5), 2);
+        assert_eq!(ramp().bet(-5), Money::from_dollars(50));
+    }
+
+    #[test]
+    fn counts_between_steps_bet_the_lower_step() {
+        assert_eq!(ramp().units(3), 4);
+        assert_eq!(ramp().bet(3), Money::from_dollars(100));
+    }
+
+    #[test]
+    fn counts_above_the_top_step_bet_the_top_step() {
+        assert_eq!(ramp().units(10), 12);
+        assert_eq!(ramp().bet(10), Money::from_dollars(300));
+    }
+
+    #[test]
+    fn bets_are_clamped_to_the_table_maximum() {
+        let mut betting = ramp();
+        betting.table_max = Money::from_dollars(150);
+        assert_eq!(betting.bet(10), Money::from_dollars(150));
+        assert_eq!(betting.bet(4), Money::from_dollars(150));
+        assert_eq!(betting.bet(2), Money::from_dollars(100));
+    }
+}
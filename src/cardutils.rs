@@ -1,6 +1,12 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 use smallvec::{SmallVec, smallvec};
+use crate::parsing::*;
+use crate::shuffle::*;
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Card {
     rank: Rank,
@@ -17,8 +23,7 @@ impl Card {
         self.rank.value()
     }
     #[inline(always)]
-    pub fn rand() -> Self {
-        let mut rng = rand::thread_rng();
+    pub fn rand<R: Rng + ?Sized>(rng: &mut R) -> Self {
         let rank_num = rng.gen_range(0..13);
         let rank = match rank_num {
             0 => Rank::Ace,
@@ -39,24 +44,98 @@ impl Card {
         };
         Card::new(rank, suit)
     }
+    // Position of the card within a single deck
+    #[inline(always)]
+    fn index(&self) -> usize {
+        let rank = match self.rank {
+            Rank::Ace => 0,
+            Rank::Number(n) => n as usize - 1,
+            Rank::Jack => 10,
+            Rank::Queen => 11,
+            Rank::King => 12,
+        };
+        rank * 4 + self.suit as usize
+    }
     #[inline(always)]
     pub fn get_rank(&self) -> Rank {
         self.rank
     }
     #[inline(always)]
-    pub fn hilo_value(&self) -> isize {
-        match self.rank {
-            Rank::Number(2)
-            | Rank::Number(3)
-            | Rank::Number(4)
-            | Rank::Number(5)
-            | Rank::Number(6) => 1,
-            Rank::Number(7) | Rank::Number(8) | Rank::Number(9) => 0,
-            Rank::Number(10) | Rank::Jack | Rank::Queen | Rank::King | Rank::Ace => -1,
-            _ => unreachable!(),
+    pub fn get_suit(&self) -> Suit {
+        self.suit
+    }
+}
+// Cards are written as a rank, A 2-9 T J Q K, followed by a suit, s c h d, so the
+// ace of spades is "As". "10" is also read as a ten
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rank = match self.rank {
+            Rank::Ace => 'A',
+            Rank::Number(10) => 'T',
+            Rank::Number(n) => (b'0' + n) as char,
+            Rank::Jack => 'J',
+            Rank::Queen => 'Q',
+            Rank::King => 'K',
+        };
+        let suit = match self.suit {
+            Suit::Spades => 's',
+            Suit::Clubs => 'c',
+            Suit::Hearts => 'h',
+            Suit::Diamonds => 'd',
+        };
+        write!(f, "{}{}", rank, suit)
+    }
+}
+
+impl FromStr for Card {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid card \"{}\"", text);
+        let split = text.len().checked_sub(1).filter(|&split| text.is_char_boundary(split));
+        let (rank, suit) = text.split_at(split.ok_or_else(invalid)?);
+        let rank = match rank {
+            "A" | "a" => Rank::Ace,
+            "T" | "t" | "10" => Rank::Number(10),
+            "J" | "j" => Rank::Jack,
+            "Q" | "q" => Rank::Queen,
+            "K" | "k" => Rank::King,
+            _ => match rank.parse::<u8>() {
+                Ok(n @ 2..=9) => Rank::Number(n),
+                _ => return Err(invalid()),
+            },
+        };
+        let suit = match suit {
+            "s" | "S" => Suit::Spades,
+            "c" | "C" => Suit::Clubs,
+            "h" | "H" => Suit::Hearts,
+            "d" | "D" => Suit::Diamonds,
+            _ => return Err(invalid()),
+        };
+        Ok(Card::new(rank, suit))
+    }
+}
+
+// Reads whitespace separated cards like "As Td 8h". Anything after a # is a comment
+pub fn parse_cards(text: &str) -> Result<SmallVec<[Card; 4]>, ParseError> {
+    let mut cards = smallvec![];
+    for (line, tokens) in token_lines(text) {
+        for token in tokens {
+            let card = token
+                .parse()
+                .map_err(|message| ParseError::Parse { line, message })?;
+            cards.push(card);
         }
     }
+    Ok(cards)
 }
+
+#[inline(always)]
+pub fn format_cards(cards: &[Card]) -> String {
+    let cards: Vec<String> = cards.iter().map(|card| card.to_string()).collect();
+    cards.join(" ")
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Rank {
     Ace,
@@ -86,30 +165,147 @@ pub enum Suit {
 #[derive(Debug, Clone)]
 pub struct Shoe {
     cards: SmallVec<[Card; 4]>,
+    // Cards left in the shoe when the cut card comes out
+    cut_card: usize,
+    // The discard tray, every card played since the last shuffle
+    discards: SmallVec<[Card; 4]>,
+    // How many of each card the shoe started with
+    composition: [u16; 52],
+    // Set when the shoe runs out mid-round and the discards are shuffled back in
+    ran_out: bool,
+    // Shuffles the discards when the shoe runs out, which happens inside a deal
+    rng: StdRng,
 }
 
 impl Shoe {
     #[inline(always)]
-    pub fn new(num_decks: u8) -> Shoe {
+    pub fn new<R: Rng + ?Sized>(num_decks: u8, rng: &mut R) -> Shoe {
         let mut cards = smallvec![];
-        let mut rng = rand::thread_rng();
 
         // Populate the shoe with cards
         for _ in 0..(num_decks) {
             Self::push_fresh_deck(&mut cards);
         }
-        cards.shuffle(&mut rng);
+        cards.shuffle(rng);
 
-        Shoe { cards }
+        Self::stacked(cards, StdRng::seed_from_u64(rng.gen()))
+    }
+    // A shoe that deals exactly these cards, first card first, for setting up
+    // specific situations. The cut card comes out once the last one is dealt
+    #[inline(always)]
+    pub fn from_cards(cards: &[Card]) -> Shoe {
+        Self::stacked(cards.iter().rev().copied().collect(), StdRng::seed_from_u64(0))
+    }
+    #[inline(always)]
+    pub fn parse(text: &str) -> Result<Shoe, ParseError> {
+        Ok(Self::from_cards(&parse_cards(text)?))
+    }
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Shoe, ParseError> {
+        Self::parse(&read_text(path)?)
+    }
+    // The cards still to come, in the order they'll be dealt
+    #[inline(always)]
+    pub fn cards(&self) -> impl Iterator<Item = &Card> {
+        self.cards.iter().rev()
     }
     #[inline(always)]
+    fn stacked(cards: SmallVec<[Card; 4]>, rng: StdRng) -> Shoe {
+        let mut composition = [0; 52];
+        for card in cards.iter() {
+            composition[card.index()] += 1;
+        }
+        Shoe {
+            cards,
+            cut_card: 0,
+            discards: smallvec![],
+            composition,
+            ran_out: false,
+            rng,
+        }
+    }
+    // A round that outlasts the shoe carries on with the discard tray, shuffled
+    // back in the way a dealer would
+    #[inline(always)]
     pub fn deal(&mut self) -> Card {
-        self.cards.pop().expect("No cards left in shoe")
+        if self.cards.is_empty() {
+            self.cards.extend(self.discards.drain(..));
+            self.cards.shuffle(&mut self.rng);
+            self.ran_out = true;
+        }
+        self.cards.pop().expect("No cards left in shoe or discard tray")
+    }
+    // Whether the discards had to be shuffled in mid-round since the last shuffle
+    #[inline(always)]
+    pub fn ran_out(&self) -> bool {
+        self.ran_out
     }
     #[inline(always)]
     pub fn cards_left(&self) -> usize {
         self.cards.len()
     }
+    // Puts the cut card in front of the last cards_behind cards
+    #[inline(always)]
+    pub fn place_cut_card(&mut self, cards_behind: usize) {
+        self.cut_card = cards_behind;
+    }
+    #[inline(always)]
+    pub fn cut_card_out(&self) -> bool {
+        self.cards.len() <= self.cut_card
+    }
+    // Gathers the discard tray back into the shoe and shuffles everything
+    #[inline(always)]
+    pub fn reshuffle<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.cards.extend(self.discards.drain(..));
+        self.cards.shuffle(rng);
+        self.ran_out = false;
+    }
+    // Same, but shuffled by hand, so the order the cards were discarded in and
+    // where the cut card was still show through
+    #[inline(always)]
+    pub fn hand_shuffle<R: Rng + ?Sized>(&mut self, procedure: &HandShuffle, rng: &mut R) {
+        self.cards = procedure.shuffle(&self.cards, &self.discards, rng);
+        self.discards.clear();
+        self.ran_out = false;
+    }
+    #[inline(always)]
+    pub fn discard(&mut self, cards: &[Card]) {
+        self.discards.extend_from_slice(cards);
+    }
+    #[inline(always)]
+    pub fn discards(&self) -> &[Card] {
+        &self.discards
+    }
+    // Puts each discard back at a random position in the shoe, below the top
+    // reserve cards, which are dealt first whatever comes back
+    #[inline(always)]
+    pub fn shuffle_in_discards<R: Rng + ?Sized>(&mut self, reserve: usize, rng: &mut R) {
+        for card in self.discards.drain(..) {
+            let bottom = self.cards.len().saturating_sub(reserve);
+            self.cards.insert(rng.gen_range(0..=bottom), card);
+        }
+        self.ran_out = false;
+    }
+    // Panics unless the shoe, the discard tray and the cards in play hold exactly
+    // the cards the shoe started with
+    pub fn audit(&self, in_play: &[Card]) {
+        let mut counts = [0u16; 52];
+        for card in self.cards.iter().chain(self.discards.iter()).chain(in_play) {
+            counts[card.index()] += 1;
+        }
+        let mut deck = smallvec![];
+        Self::push_fresh_deck(&mut deck);
+        for card in deck {
+            let count = counts[card.index()];
+            let expected = self.composition[card.index()];
+            assert!(
+                count == expected,
+                "Card audit failed: {} found {} times, expected {}",
+                card,
+                count,
+                expected
+            );
+        }
+    }
     #[inline(always)]
     fn push_fresh_deck(cards: &mut SmallVec<[Card; 4]>) {
         cards.push(Card::new(Rank::Ace, Suit::Spades));
@@ -170,39 +366,66 @@ impl Shoe {
     }
 }
 
+// The cards still to come, in the order they'll be dealt
+impl fmt::Display for Shoe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cards: SmallVec<[Card; 4]> = self.cards().copied().collect();
+        write!(f, "{}", format_cards(&cards))
+    }
+}
+
+// Cards in a hand along with a running total kept up to date as cards are added
+// and removed, so value queries don't need to walk the cards
 #[derive(Debug, Clone)]
 pub struct HandCards {
-    pub cards: SmallVec<[Card; 4]>,
+    cards: SmallVec<[Card; 4]>,
+    // Total with every ace counted as one
+    hard_total: u8,
+    aces: u8,
 }
 
 impl HandCards {
     #[inline(always)]
     pub fn new() -> Self {
-        HandCards { cards: smallvec![] }
+        HandCards {
+            cards: smallvec![],
+            hard_total: 0,
+            aces: 0,
+        }
     }
     #[inline(always)]
-    pub fn from(shoe: &mut Shoe) -> (Self, isize) {
-        let cards = smallvec![shoe.deal(), shoe.deal()];
-        (
-            HandCards {
-                cards: cards.clone(),
-            },
-            cards[0].hilo_value() + cards[1].hilo_value()
-        )
+    pub fn from(shoe: &mut Shoe) -> Self {
+        let mut hand = Self::new();
+        hand.push(shoe.deal());
+        hand.push(shoe.deal());
+        hand
+    }
+    #[inline(always)]
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
     }
     #[inline(always)]
     pub fn len(&self) -> usize {
         self.cards.len()
     }
     #[inline(always)]
-    pub fn hit(&mut self, shoe: &mut Shoe) -> isize {
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+    #[inline(always)]
+    pub fn hit(&mut self, shoe: &mut Shoe) -> Card {
         let card = shoe.deal();
-        self.cards.push(card);
-        card.hilo_value()
-
+        self.push(card);
+        card
     }
     #[inline(always)]
     pub fn push(&mut self, card: Card) {
+        if card.rank == Rank::Ace {
+            self.aces += 1;
+            self.hard_total += 1;
+        } else {
+            self.hard_total += card.value();
+        }
         self.cards.push(card);
     }
     // Returns (Number value, soft or hard)
@@ -216,11 +439,13 @@ impl HandCards {
         self.cards[0]
     }
 
+    // Soft while one ace can count as eleven without busting. Only one ever can,
+    // two would already make 22
     #[inline(always)]
     pub fn value_type(&self) -> ValueType {
-        match !self.has_ace() || self.raw_value() > 21 {
-            true => ValueType::Hard,
-            false => ValueType::Soft,
+        match self.aces > 0 && self.hard_total + 10 <= 21 {
+            true => ValueType::Soft,
+            false => ValueType::Hard,
         }
     }
 
@@ -229,44 +454,49 @@ impl HandCards {
         self.cards.len() == 2 && self.cards[0].rank == self.cards[1].rank
     }
 
+    // Two cards of the same value, so any two ten-valued cards count
+    #[inline(always)]
+    pub fn is_value_pair(&self) -> bool {
+        self.cards.len() == 2 && self.cards[0].value() == self.cards[1].value()
+    }
+
     #[inline(always)]
     pub fn is_bust(&self) -> bool {
-        self.num_value() > 21
+        self.hard_total > 21
     }
 
     #[inline(always)]
-    pub fn has_ace(&self) -> bool {
-        for card in self.cards.iter() {
-            if card.rank == Rank::Ace {
-                return true;
-            }
-        }
-        false
+    pub fn is_blackjack(&self) -> bool {
+        self.cards.len() == 2 && self.aces == 1 && self.hard_total == 11
     }
+
     #[inline(always)]
-    fn raw_value(&self) -> u8 {
-        let mut value = 0;
-        for card in self.cards.iter() {
-            value += card.value();
-        }
-        value
+    pub fn has_ace(&self) -> bool {
+        self.aces > 0
     }
+
     #[inline(always)]
     pub fn num_value(&self) -> u8 {
-        let mut value = self.raw_value();
-        if self.has_ace() && value > 21 {
-            value -= 10;
+        match self.value_type() {
+            ValueType::Soft => self.hard_total + 10,
+            ValueType::Hard => self.hard_total,
         }
-        value
     }
 
     #[inline(always)]
     pub fn pop(&mut self) -> Card {
-        self.cards.pop().expect("No cards left in hand")
+        let card = self.cards.pop().expect("No cards left in hand");
+        if card.rank == Rank::Ace {
+            self.aces -= 1;
+            self.hard_total -= 1;
+        } else {
+            self.hard_total -= card.value();
+        }
+        card
     }
 
     #[inline(always)]
-    pub fn dealer_play(&mut self, shoe: &mut Shoe, hit_soft_17: bool) -> isize {
+    pub fn dealer_play(&mut self, shoe: &mut Shoe, hit_soft_17: bool) {
         match hit_soft_17 {
             true => self.dealer_play_h17(shoe),
             false => self.dealer_play_s17(shoe),
@@ -274,34 +504,23 @@ impl HandCards {
     }
 
     #[inline(always)]
-    fn dealer_play_s17(&mut self, shoe: &mut Shoe) -> isize {
-        let mut hilo_value = self.cards[1].hilo_value();
-        let mut dealer_index = 2;
+    fn dealer_play_s17(&mut self, shoe: &mut Shoe) {
         while self.num_value() < 17 {
             self.hit(shoe);
-            hilo_value += self.cards[dealer_index].hilo_value();
-            dealer_index += 1;
         }
-        hilo_value
     }
 
     #[inline(always)]
-    fn dealer_play_h17(&mut self, shoe: &mut Shoe) -> isize {
-        let mut hilo_value = self.cards[1].hilo_value();
-        let mut dealer_index = 2;
+    fn dealer_play_h17(&mut self, shoe: &mut Shoe) {
         loop {
-            let value = self.num_value();
+            let (value, value_type) = self.value();
             match value {
                 0..=16 => {
                     self.hit(shoe);
-                    hilo_value += self.cards[dealer_index].hilo_value();
-                    dealer_index += 1;
                 }
                 17 => {
-                    if self.has_ace() {
+                    if value_type == ValueType::Soft {
                         self.hit(shoe);
-                        hilo_value += self.cards[dealer_index].hilo_value();
-                        dealer_index += 1;
                     } else {
                         break;
                     }
@@ -309,7 +528,6 @@ impl HandCards {
                 _ => break,
             }
         }
-        hilo_value
     }
 }
 
@@ -325,3 +543,147 @@ pub enum ValueType {
     Hard,
     Soft,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RANKS: [Rank; 13] = [
+        Rank::Ace,
+        Rank::Number(2),
+        Rank::Number(3),
+        Rank::Number(4),
+        Rank::Number(5),
+        Rank::Number(6),
+        Rank::Number(7),
+        Rank::Number(8),
+        Rank::Number(9),
+        Rank::Number(10),
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+    ];
+
+    fn hard_value(rank: Rank) -> u8 {
+        match rank {
+            Rank::Ace => 1,
+            _ => rank.value(),
+        }
+    }
+
+    // Highest total that doesn't bust over every way of counting the aces, or the
+    // lowest total when they all bust
+    fn expected_value(ranks: &[Rank]) -> (u8, ValueType) {
+        let hard: u8 = ranks.iter().map(|rank| hard_value(*rank)).sum();
+        let aces = ranks.iter().filter(|rank| **rank == Rank::Ace).count() as u8;
+        let mut value = (hard, ValueType::Hard);
+        for elevens in 1..=aces {
+            if hard + 10 * elevens <= 21 {
+                value = (hard + 10 * elevens, ValueType::Soft);
+            }
+        }
+        value
+    }
+
+    fn hand_of(ranks: &[Rank]) -> HandCards {
+        let mut hand = HandCards::new();
+        for rank in ranks {
+            hand.push(Card::new(*rank, Suit::Spades));
+        }
+        hand
+    }
+
+    fn check(hand: &HandCards, ranks: &[Rank]) {
+        let (value, value_type) = expected_value(ranks);
+        let is_ten = |rank: &Rank| rank.value() == 10;
+        let blackjack = ranks.len() == 2 && ranks.contains(&Rank::Ace) && ranks.iter().any(is_ten);
+        assert_eq!(hand.len(), ranks.len(), "{:?}", ranks);
+        assert_eq!(hand.value(), (value, value_type), "{:?}", ranks);
+        assert_eq!(hand.is_bust(), value > 21, "{:?}", ranks);
+        assert_eq!(hand.is_blackjack(), blackjack, "{:?}", ranks);
+        assert_eq!(hand.has_ace(), ranks.contains(&Rank::Ace), "{:?}", ranks);
+        assert_eq!(
+            hand.is_pair(),
+            ranks.len() == 2 && ranks[0] == ranks[1],
+            "{:?}",
+            ranks
+        );
+    }
+
+    // Every multiset of ranks, in rank order, whose total with aces as one is at
+    // most 21
+    fn multisets(start: usize, ranks: &mut Vec<Rank>, total: u8, found: &mut Vec<Vec<Rank>>) {
+        found.push(ranks.clone());
+        for (index, rank) in RANKS.iter().enumerate().skip(start) {
+            if total + hard_value(*rank) <= 21 {
+                ranks.push(*rank);
+                multisets(index, ranks, total + hard_value(*rank), found);
+                ranks.pop();
+            }
+        }
+    }
+
+    #[test]
+    fn values_match_for_every_hand_up_to_21() {
+        let mut found = vec![];
+        multisets(0, &mut vec![], 0, &mut found);
+        for ranks in found {
+            check(&hand_of(&ranks), &ranks);
+            let reversed: Vec<Rank> = ranks.iter().rev().copied().collect();
+            check(&hand_of(&reversed), &reversed);
+        }
+    }
+
+    #[test]
+    fn drawing_to_every_hand_up_to_21_and_removing_the_card() {
+        let mut found = vec![];
+        multisets(0, &mut vec![], 0, &mut found);
+        for ranks in found {
+            let mut hand = hand_of(&ranks);
+            for rank in RANKS {
+                let card = Card::new(rank, Suit::Hearts);
+                hand.push(card);
+                let mut drawn = ranks.clone();
+                drawn.push(rank);
+                check(&hand, &drawn);
+                assert_eq!(hand.pop(), card);
+                check(&hand, &ranks);
+            }
+        }
+    }
+
+    #[test]
+    fn multiple_aces_count_as_one_when_needed() {
+        let ace = Rank::Ace;
+        let nine = Rank::Number(9);
+        assert_eq!(
+            hand_of(&[ace, ace, ace, nine]).value(),
+            (12, ValueType::Hard)
+        );
+        assert_eq!(hand_of(&[ace, ace]).value(), (12, ValueType::Soft));
+        assert_eq!(hand_of(&[ace, ace, nine]).value(), (21, ValueType::Soft));
+        assert_eq!(
+            hand_of(&[ace, ace, Rank::King]).value(),
+            (12, ValueType::Hard)
+        );
+    }
+
+    #[test]
+    fn card_notation_round_trips() {
+        let text = "As Td 8h 2c Kd Qs Jh 10c # comment\n9d";
+        let cards = parse_cards(text).unwrap();
+        assert_eq!(cards.len(), 9);
+        assert_eq!(cards[0], Card::new(Rank::Ace, Suit::Spades));
+        assert_eq!(cards[7], Card::new(Rank::Number(10), Suit::Clubs));
+        assert_eq!(format_cards(&cards), "As Td 8h 2c Kd Qs Jh Tc 9d");
+        let shoe = Shoe::from_cards(&cards);
+        assert_eq!(shoe.to_string(), "As Td 8h 2c Kd Qs Jh Tc 9d");
+        for bad in ["", "A", "1s", "Ax", "11h", "As\u{2660}"] {
+            assert!(bad.parse::<Card>().is_err(), "{}", bad);
+        }
+        match parse_cards("As\n8h Zz") {
+            Err(ParseError::Parse { line, .. }) => assert_eq!(line, 2),
+            other => panic!("{:?}", other),
+        }
+    }
+}
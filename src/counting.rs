@@ -0,0 +1,123 @@
+use std::fmt::Debug;
+use crate::cardutils::*;
+
+// A card counting system. Tags are stored as integers scaled by DENOMINATOR so
+// fractional systems like Wong Halves keep integer running counts.
+pub trait CountingSystem: Debug + Clone + Copy + Default + Send + 'static {
+    // Tags for A, 2, 3, 4, 5, 6, 7, 8, 9 and ten-valued cards
+    const TAGS: [isize; 10];
+    const DENOMINATOR: isize = 1;
+    // Unbalanced systems don't convert to a true count, Count::true_count gives
+    // their running count instead
+    const BALANCED: bool = true;
+
+    #[inline(always)]
+    fn tag(card: Card) -> isize {
+        Self::TAGS[rank_index(card.get_rank())]
+    }
+
+    // Running count off the top of a fresh shoe, scaled by DENOMINATOR
+    #[inline(always)]
+    fn initial_running_count(_decks_in_shoe: u8) -> isize {
+        0
+    }
+}
+
+#[inline(always)]
+pub fn rank_index(rank: Rank) -> usize {
+    match rank {
+        Rank::Ace => 0,
+        Rank::Number(n) => (n - 1) as usize,
+        Rank::Jack | Rank::Queen | Rank::King => 9,
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HiLo;
+
+impl CountingSystem for HiLo {
+    //                        A  2  3  4  5  6  7  8  9   T
+    const TAGS: [isize; 10] = [-1, 1, 1, 1, 1, 1, 0, 0, 0, -1];
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct KnockOut;
+
+impl CountingSystem for KnockOut {
+    //                        A  2  3  4  5  6  7  8  9   T
+    const TAGS: [isize; 10] = [-1, 1, 1, 1, 1, 1, 1, 0, 0, -1];
+    const BALANCED: bool = false;
+
+    #[inline(always)]
+    fn initial_running_count(decks_in_shoe: u8) -> isize {
+        4 - 4 * decks_in_shoe as isize
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HiOptI;
+
+impl CountingSystem for HiOptI {
+    //                        A  2  3  4  5  6  7  8  9   T
+    const TAGS: [isize; 10] = [0, 0, 1, 1, 1, 1, 0, 0, 0, -1];
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HiOptII;
+
+impl CountingSystem for HiOptII {
+    //                        A  2  3  4  5  6  7  8  9   T
+    const TAGS: [isize; 10] = [0, 1, 1, 2, 2, 1, 1, 0, 0, -2];
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OmegaII;
+
+impl CountingSystem for OmegaII {
+    //                        A  2  3  4  5  6  7  8   9   T
+    const TAGS: [isize; 10] = [0, 1, 1, 2, 2, 2, 1, 0, -1, -2];
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Zen;
+
+impl CountingSystem for Zen {
+    //                        A  2  3  4  5  6  7  8  9   T
+    const TAGS: [isize; 10] = [-1, 1, 1, 2, 2, 2, 1, 0, 0, -2];
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WongHalves;
+
+impl CountingSystem for WongHalves {
+    // Tags are in half points
+    //                        A  2  3  4  5  6  7  8   9   T
+    const TAGS: [isize; 10] = [-2, 1, 2, 2, 3, 2, 1, 0, -1, -2];
+    const DENOMINATOR: isize = 2;
+}
+
+// Halves is the same system as Wong Halves, under the name it's usually published with
+pub type Halves = WongHalves;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Red7;
+
+impl CountingSystem for Red7 {
+    //                        A  2  3  4  5  6  7  8  9   T
+    const TAGS: [isize; 10] = [-1, 1, 1, 1, 1, 1, 0, 0, 0, -1];
+    const BALANCED: bool = false;
+
+    // Hi-Lo, except red sevens count as a low card
+    #[inline(always)]
+    fn tag(card: Card) -> isize {
+        match (card.get_rank(), card.get_suit()) {
+            (Rank::Number(7), Suit::Hearts | Suit::Diamonds) => 1,
+            (rank, _) => Self::TAGS[rank_index(rank)],
+        }
+    }
+
+    #[inline(always)]
+    fn initial_running_count(decks_in_shoe: u8) -> isize {
+        -2 * decks_in_shoe as isize
+    }
+}
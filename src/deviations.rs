@@ -0,0 +1,222 @@
+use std::path::Path;
+use crate::basicstrategy::*;
+use crate::parsing::*;
+
+// The hand an index play applies to, keyed the same way as the strategy tables
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HandKey {
+    Hard(u8),
+    Soft(u8),
+    // Card value of the pair, 11 for aces
+    Pair(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition {
+    AtLeast(isize),
+    Below(isize),
+}
+
+impl Condition {
+    #[inline(always)]
+    pub fn holds(&self, true_count: isize) -> bool {
+        match *self {
+            Condition::AtLeast(threshold) => true_count >= threshold,
+            Condition::Below(threshold) => true_count < threshold,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IndexPlay {
+    pub hand: HandKey,
+    // Card value of the dealer upcard, 11 for aces
+    pub dealer: u8,
+    pub condition: Condition,
+    pub decision: Decision,
+}
+
+// Count-based overrides of basic strategy, checked in order
+#[derive(Debug, Clone, Default)]
+pub struct IndexPlays {
+    pub plays: Vec<IndexPlay>,
+    // Take insurance at or above this true count
+    pub insurance: Option<isize>,
+}
+
+impl IndexPlays {
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Hi-Lo Illustrious 18, Fab 4 surrenders and insurance
+    pub fn illustrious_18() -> Self {
+        Self::parse(include_str!("../data/illustrious18_fab4.txt"))
+            .expect("Bundled index plays are invalid")
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        Self::parse(&read_text(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut index_plays = Self::new();
+        for (line, tokens) in token_lines(text) {
+            let error = |message: String| ParseError::Parse { line, message };
+            match tokens.as_slice() {
+                ["insurance", ">=", count] => {
+                    index_plays.insurance = Some(parse_count(count).map_err(error)?);
+                }
+                [kind, total, "vs", dealer, condition, count, play] => {
+                    index_plays.plays.push(IndexPlay {
+                        hand: parse_hand(kind, total).map_err(error)?,
+                        dealer: parse_card_value(dealer).map_err(error)?,
+                        condition: parse_condition(condition, count).map_err(error)?,
+                        decision: parse_decision(play).map_err(error)?,
+                    });
+                }
+                _ => {
+                    let play = tokens.join(" ");
+                    return Err(error(format!("Unrecognized index play \"{}\"", play)));
+                }
+            }
+        }
+        Ok(index_plays)
+    }
+
+    // First index play for the hand whose condition holds and that can be played
+    #[inline(always)]
+    pub fn lookup(
+        &self,
+        hand: HandKey,
+        dealer: u8,
+        true_count: isize,
+        can_surrender: bool,
+    ) -> Option<Decision> {
+        self.plays
+            .iter()
+            .find(|play| {
+                play.hand == hand
+                    && play.dealer == dealer
+                    && (can_surrender || play.decision != Decision::Surrender)
+                    && play.condition.holds(true_count)
+            })
+            .map(|play| play.decision)
+    }
+
+    #[inline(always)]
+    pub fn take_insurance(&self, true_count: isize) -> bool {
+        match self.insurance {
+            Some(threshold) => true_count >= threshold,
+            None => false,
+        }
+    }
+}
+
+fn parse_hand(kind: &str, total: &str) -> Result<HandKey, String> {
+    match kind {
+        "hard" => Ok(HandKey::Hard(parse_total(total)?)),
+        "soft" => Ok(HandKey::Soft(parse_total(total)?)),
+        "pair" => Ok(HandKey::Pair(parse_card_value(total)?)),
+        _ => Err(format!("Unknown hand type \"{}\"", kind)),
+    }
+}
+
+fn parse_total(total: &str) -> Result<u8, String> {
+    match total.parse::<u8>() {
+        Ok(value @ 4..=21) => Ok(value),
+        _ => Err(format!("Invalid hand total \"{}\"", total)),
+    }
+}
+
+fn parse_card_value(card: &str) -> Result<u8, String> {
+    match card {
+        "A" | "a" => Ok(11),
+        "T" | "t" => Ok(10),
+        _ => match card.parse::<u8>() {
+            Ok(value @ 2..=10) => Ok(value),
+            _ => Err(format!("Invalid card \"{}\"", card)),
+        },
+    }
+}
+
+fn parse_count(count: &str) -> Result<isize, String> {
+    count
+        .parse::<isize>()
+        .map_err(|_| format!("Invalid true count \"{}\"", count))
+}
+
+fn parse_condition(condition: &str, count: &str) -> Result<Condition, String> {
+    let count = parse_count(count)?;
+    match condition {
+        ">=" => Ok(Condition::AtLeast(count)),
+        "<" => Ok(Condition::Below(count)),
+        _ => Err(format!("Unknown condition \"{}\"", condition)),
+    }
+}
+
+fn parse_decision(play: &str) -> Result<Decision, String> {
+    match play {
+        "hit" => Ok(Decision::Hit),
+        "stand" => Ok(Decision::Stand),
+        "double" => Ok(Decision::Double),
+        "split" => Ok(Decision::Split),
+        "surrender" => Ok(Decision::Surrender),
+        _ => Err(format!("Unknown play \"{}\"", play)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_plays_parse() {
+        let plays = IndexPlays::illustrious_18();
+        assert_eq!(plays.insurance, Some(3));
+        assert_eq!(plays.plays.len(), 22);
+        assert_eq!(
+            plays.plays[4],
+            IndexPlay {
+                hand: HandKey::Hard(15),
+                dealer: 10,
+                condition: Condition::Below(0),
+                decision: Decision::Hit,
+            }
+        );
+    }
+
+    #[test]
+    fn illustrious_18_plays_start_at_their_index() {
+        let plays = IndexPlays::illustrious_18();
+        assert_eq!(plays.lookup(HandKey::Hard(16), 10, 0, false), Some(Decision::Stand));
+        assert_eq!(plays.lookup(HandKey::Hard(16), 10, -1, false), None);
+        assert_eq!(plays.lookup(HandKey::Pair(10), 6, 4, false), Some(Decision::Split));
+        assert_eq!(plays.lookup(HandKey::Pair(10), 6, 3, false), None);
+        assert_eq!(plays.lookup(HandKey::Hard(11), 11, 1, false), Some(Decision::Double));
+        assert_eq!(plays.lookup(HandKey::Hard(11), 11, 0, false), None);
+        assert_eq!(plays.lookup(HandKey::Hard(12), 4, -1, false), Some(Decision::Hit));
+        assert_eq!(plays.lookup(HandKey::Hard(12), 4, 0, false), None);
+        assert!(plays.take_insurance(3));
+        assert!(!plays.take_insurance(2));
+    }
+
+    #[test]
+    fn fab_4_surrenders_only_when_surrender_is_offered() {
+        let plays = IndexPlays::illustrious_18();
+        assert_eq!(plays.lookup(HandKey::Hard(14), 10, 3, true), Some(Decision::Surrender));
+        assert_eq!(plays.lookup(HandKey::Hard(14), 10, 2, true), None);
+        assert_eq!(plays.lookup(HandKey::Hard(15), 10, 0, true), Some(Decision::Surrender));
+        assert_eq!(plays.lookup(HandKey::Hard(15), 10, -1, true), Some(Decision::Hit));
+        // Without surrender 15 against a ten falls through to the Illustrious 18
+        assert_eq!(plays.lookup(HandKey::Hard(15), 10, 4, false), Some(Decision::Stand));
+        assert_eq!(plays.lookup(HandKey::Hard(15), 10, 3, false), None);
+    }
+
+    #[test]
+    fn errors_name_the_line() {
+        let error = IndexPlays::parse("insurance >= 3\n\nhard 22 vs 10 >= 0 stand").unwrap_err();
+        assert_eq!(error.to_string(), "line 3: Invalid hand total \"22\"");
+    }
+}
@@ -1,23 +1,52 @@
 use smallvec::{SmallVec, smallvec};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use crate::basicstrategy::*;
+use crate::betting::*;
 use crate::cardutils::*;
+use crate::counting::*;
+use crate::deviations::*;
+use crate::money::*;
+use crate::results::*;
+use crate::shuffle::*;
 #[derive(Debug, Clone)]
 pub struct GameRules {
     pub decks_in_shoe: u8,
-    pub deck_penetration: usize,
+    pub penetration: Penetration,
+    // The cut card goes anywhere up to this many cards either side of the
+    // penetration point
+    pub cut_card_spread: usize,
+    pub shuffling: Shuffling,
+    // How the dealer shuffles a cut card game, None for a perfectly random shuffle.
+    // The first shoe of a game always starts out randomly shuffled
+    pub hand_shuffle: Option<HandShuffle>,
     pub hit_soft_17: bool,
     pub double_after_split: bool,
     pub surrender: Option<Surrender>,
     pub resplit_aces: bool,
     pub hit_split_aces: bool,
     pub double_split_aces: bool,
+    pub doubling: DoubleRule,
+    // Most hands a round can be split into, None for no limit
+    pub max_hands: Option<usize>,
+    // Same for aces, when resplitting them is allowed
+    pub max_ace_hands: Option<usize>,
+    // Allow splitting any two ten-valued cards, like J-Q, not just pairs of a rank
+    pub split_unlike_tens: bool,
+    pub hole_card: HoleCard,
+    // Pitch games deal the players' first two cards, and double down cards, face
+    // down, so they're only seen once the hand is turned over
+    pub face_down_dealing: bool,
+    pub blackjack_payout: Ratio,
+    pub payout_rounding: PayoutRounding,
 }
 
 impl GameRules {
+    #[allow(clippy::too_many_arguments)]
     #[inline(always)]
     pub fn new(
         decks_in_shoe: u8,
-        deck_penetration: usize,
+        penetration: Penetration,
         hit_soft_17: bool,
         double_after_split: bool,
         surrender: Option<Surrender>,
@@ -27,98 +56,314 @@ impl GameRules {
     ) -> Self {
         GameRules {
             decks_in_shoe,
-            deck_penetration,
+            penetration,
+            cut_card_spread: 13,
+            shuffling: Shuffling::CutCard,
+            hand_shuffle: None,
             hit_soft_17,
             double_after_split,
             surrender,
             resplit_aces,
             hit_split_aces,
             double_split_aces,
+            doubling: DoubleRule::AnyTwoCards,
+            max_hands: Some(4),
+            max_ace_hands: Some(4),
+            split_unlike_tens: false,
+            hole_card: HoleCard::Peek,
+            face_down_dealing: false,
+            blackjack_payout: Ratio::THREE_TO_TWO,
+            payout_rounding: PayoutRounding::default(),
         }
     }
+
+    // Late surrender comes after the dealer checks for blackjack, so it's only
+    // offered when the dealer peeks. Without a peek it would keep half the bet
+    // against a dealer natural, which is early surrender
+    #[inline(always)]
+    pub fn late_surrender(&self) -> bool {
+        self.surrender == Some(Surrender::Late) && self.hole_card == HoleCard::Peek
+    }
+}
+
+// How much of the shoe is dealt before the cut card
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Penetration {
+    // Fraction of the shoe, e.g. 0.75
+    Fraction(f64),
+    Decks(f64),
+}
+
+impl Penetration {
+    #[inline(always)]
+    pub fn cards_dealt(&self, decks_in_shoe: u8) -> usize {
+        let cards = match *self {
+            Penetration::Fraction(fraction) => fraction * 52.0 * decks_in_shoe as f64,
+            Penetration::Decks(decks) => decks * 52.0,
+        };
+        cards.round().max(0.0) as usize
+    }
+}
+
+// How the dealt cards get back into play
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shuffling {
+    // The whole shoe is reshuffled once the cut card comes out
+    CutCard,
+    // A continuous shuffling machine takes the discards back every batch rounds.
+    // They're shuffled in among the cards still in the machine, but never among
+    // the reserve of cards already dropped into the shoe for dealing
+    Continuous { batch: usize, reserve: usize },
+}
+
+// Which hands may be doubled
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DoubleRule {
+    AnyTwoCards,
+    NineToEleven,
+    TenToEleven,
+    // Any hand that hasn't been doubled yet, however many cards it has
+    AnyCards,
+}
+
+impl DoubleRule {
+    #[inline(always)]
+    pub fn allows(&self, cards: usize, total: u8) -> bool {
+        match self {
+            DoubleRule::AnyTwoCards => cards == 2,
+            DoubleRule::NineToEleven => cards == 2 && (9..=11).contains(&total),
+            DoubleRule::TenToEleven => cards == 2 && (10..=11).contains(&total),
+            DoubleRule::AnyCards => total < 21,
+        }
+    }
+}
+
+// Where each card of the initial deal goes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Deal {
+    Player,
+    DealerUpcard,
+    // Only dealt when the dealer takes a hole card, and only seen when turned over
+    DealerHoleCard,
+}
+
+// The order the initial deal comes out of the shoe
+pub const DEALING_ORDER: [Deal; 4] = [
+    Deal::Player,
+    Deal::DealerUpcard,
+    Deal::Player,
+    Deal::DealerHoleCard,
+];
+
+// When the dealer takes a second card and what a dealer blackjack takes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HoleCard {
+    // US style, the dealer checks for blackjack under an ace or ten before anyone
+    // acts, so only the original bets are ever lost to it
+    Peek,
+    // European no-hole-card, the second card is dealt after the players act and a
+    // dealer blackjack takes doubles and splits too
+    NoHoleCard,
+    // No hole card, but a dealer blackjack only takes the original bet
+    OriginalBetsOnly,
+}
+
+// How precisely the counter estimates the number of decks left in the shoe
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeckEstimation {
+    Exact,
+    HalfDeck,
+    FullDeck,
+    // Estimate the discard tray to the nearest half deck and subtract from the shoe size
+    DiscardTray,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrueCountRounding {
+    Floor,
+    Truncate,
+    Round,
 }
 
-// TODO implement bet spreads
 #[derive(Debug, Clone)]
-pub struct Count {
+pub struct Count<S: CountingSystem = HiLo> {
+    // Sum of the system's tags, scaled by S::DENOMINATOR
     pub running_count: isize,
     pub cards_seen: usize,
-    pub std_bet: usize,
+    // Side count of ten-valued cards seen
+    pub tens_seen: usize,
+    pub estimation: DeckEstimation,
+    pub rounding: TrueCountRounding,
+    pub system: S,
 }
 
-impl Count {
+impl<S: CountingSystem> Count<S> {
     #[inline(always)]
-    pub fn new() -> Self {
+    pub fn new(decks_in_shoe: u8) -> Self {
+        Self::with_precision(decks_in_shoe, DeckEstimation::Exact, TrueCountRounding::Floor)
+    }
+
+    #[inline(always)]
+    pub fn with_precision(
+        decks_in_shoe: u8,
+        estimation: DeckEstimation,
+        rounding: TrueCountRounding,
+    ) -> Self {
         Count {
-            running_count: 0,
+            running_count: S::initial_running_count(decks_in_shoe),
             cards_seen: 0,
-            std_bet: 100,
+            tens_seen: 0,
+            estimation,
+            rounding,
+            system: S::default(),
+        }
+    }
+
+    // Running count in the system's own units, fractional tags included
+    #[inline(always)]
+    pub fn running_count_value(&self) -> f64 {
+        self.running_count as f64 / S::DENOMINATOR as f64
+    }
+
+    #[inline(always)]
+    pub fn decks_remaining(
+        &self,
+        cards_left: usize,
+        cards_discarded: usize,
+        decks_in_shoe: u8,
+    ) -> f64 {
+        let decks_left = cards_left as f64 / 52.0;
+        match self.estimation {
+            DeckEstimation::Exact => decks_left,
+            DeckEstimation::HalfDeck => ((decks_left * 2.0).round() / 2.0).max(0.5),
+            DeckEstimation::FullDeck => decks_left.round().max(1.0),
+            DeckEstimation::DiscardTray => {
+                let decks_discarded = (cards_discarded as f64 / 26.0).round() / 2.0;
+                (decks_in_shoe as f64 - decks_discarded).max(0.5)
+            }
         }
     }
 
     #[inline(always)]
-    pub fn update(&mut self, hilo_value: isize) {
+    pub fn raw_true_count(
+        &self,
+        cards_left: usize,
+        cards_discarded: usize,
+        decks_in_shoe: u8,
+    ) -> f64 {
+        // Unbalanced systems are played off the running count, which starts from
+        // the initial running count so the betting and index thresholds line up
+        if !S::BALANCED {
+            return self.running_count_value();
+        }
+        let decks_remaining = self.decks_remaining(cards_left, cards_discarded, decks_in_shoe);
+        if decks_remaining <= 0.0 {
+            return self.running_count_value();
+        }
+        self.running_count_value() / decks_remaining
+    }
+
+    #[inline(always)]
+    pub fn true_count(
+        &self,
+        cards_left: usize,
+        cards_discarded: usize,
+        decks_in_shoe: u8,
+    ) -> isize {
+        let true_count = self.raw_true_count(cards_left, cards_discarded, decks_in_shoe);
+        match self.rounding {
+            TrueCountRounding::Floor => true_count.floor() as isize,
+            TrueCountRounding::Truncate => true_count.trunc() as isize,
+            TrueCountRounding::Round => true_count.round() as isize,
+        }
+    }
+
+    // Whether more than a third of the cards not yet seen are tens, which makes
+    // insurance's 2:1 payout worth taking
+    #[inline(always)]
+    pub fn tens_rich(&self, decks_in_shoe: u8) -> bool {
+        let tens_left = (16 * decks_in_shoe as usize).saturating_sub(self.tens_seen);
+        let cards_left = (52 * decks_in_shoe as usize).saturating_sub(self.cards_seen);
+        3 * tens_left > cards_left
+    }
+
+    #[inline(always)]
+    pub fn update(&mut self, card: Card) {
         self.cards_seen += 1;
-        self.running_count += hilo_value;
+        self.tens_seen += (card.value() == 10) as usize;
+        self.running_count += S::tag(card);
     }
 
     #[inline(always)]
-    pub fn reset(&mut self) {
+    pub fn update_all(&mut self, cards: &[Card]) {
+        for card in cards {
+            self.update(*card);
+        }
+    }
+
+    #[inline(always)]
+    pub fn reset(&mut self, decks_in_shoe: u8) {
         self.cards_seen = 0;
-        self.running_count = 0;
+        self.tens_seen = 0;
+        self.running_count = S::initial_running_count(decks_in_shoe);
     }
 }
 
+// When to insure against a dealer ace, or take even money on a natural
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InsuranceStrategy {
+    Never,
+    // Follow the insurance index in the game's index plays, if it has one
+    IndexPlays,
+    // Insure whenever the ten density side count says the bet is favorable
+    TenDensity,
+}
+
+// Cards always left behind the cut card, enough for the last round of a shoe
+const MIN_CARDS_BEHIND_CUT: usize = 16;
+
 #[derive(Debug)]
-pub struct Game {
+pub struct Game<S: CountingSystem = HiLo> {
     pub rounds_left: usize,
     pub rules: GameRules,
-    pub count: Count,
-    pub wins: usize,
-    pub losses: usize,
-    pub pushes: usize,
-    pub hits: usize,
-    pub doubles: usize,
-    pub splits: usize,
-    pub stands: usize,
-    pub surrenders: usize,
-    pub blackjacks: usize,
-    pub amount_bet: usize,
-    pub amount_won: usize,
-    pub amount_lost: usize,
+    pub count: Count<S>,
+    pub betting: BettingStrategy,
+    // Index plays layered over basic strategy, None plays basic strategy only
+    pub deviations: Option<IndexPlays>,
+    pub insurance: InsuranceStrategy,
+    // Check every card is accounted for at the end of each round
+    pub audit: bool,
+    pub results: SimulationResults,
     pub current_round: Round,
-    pub bankroll: usize,
+    pub bankroll: Money,
+    pub rng: StdRng,
 }
 
-impl Game {
+impl<S: CountingSystem> Game<S> {
     #[inline(always)]
-    pub fn new(rules: GameRules, bankroll: usize, rounds_to_play: usize) -> Self {
-        let mut shoe = Shoe::new(rules.decks_in_shoe);
-        let count = Count::new();
+    pub fn new(rules: GameRules, bankroll: Money, rounds_to_play: usize, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let shoe = Self::new_shoe(&rules, &mut rng);
         Game {
             rounds_left: rounds_to_play,
             rules: rules.clone(),
-            count: count.clone(),
-            wins: 0,
-            blackjacks: 0,
+            count: Count::new(rules.decks_in_shoe),
+            betting: BettingStrategy::default(),
+            deviations: None,
+            insurance: InsuranceStrategy::IndexPlays,
+            audit: false,
+            results: SimulationResults::new(),
             current_round: Round {
-                shoe: Shoe::new(rules.decks_in_shoe),
+                shoe,
                 rules,
-                hands: smallvec![Hand::new(count.std_bet, &mut shoe).0],
-                dealer: HandCards::from(&mut shoe).0,
-                has_split_aces: false,
+                hands: smallvec![],
+                dealer: HandCards::new(),
+                initial_bet: Money::ZERO,
+                insurance: Money::ZERO,
+                face_down: smallvec![],
             },
-            losses: 0,
-            pushes: 0,
-            surrenders: 0,
-            amount_bet: 0,
-            amount_lost: 0,
-            amount_won: 0,
-            doubles: 0,
-            hits: 0,
-            stands: 0,
-            splits: 0,
             bankroll,
+            rng,
         }
     }
 
@@ -126,7 +371,7 @@ impl Game {
     pub fn play(mut self) -> Self {
         while self.rounds_left > 0 {
             self.play_round();
-            if self.bankroll <= (self.count.std_bet << 3) {
+            if self.bankroll <= self.betting.table_max * 8 {
                 return self;
             }
             self.rounds_left -= 1;
@@ -137,10 +382,24 @@ impl Game {
     #[inline(always)]
     fn play_round(&mut self) {
         self.new_round();
+        self.results.rounds += 1;
         let mut hand_index = 0;
         let mut play;
-        let mut surrendered = false;
+        if self.can_surrender_early() && BasicStrategyLUT::early_surrender(self) {
+            self.surrender(0);
+        } else if self.current_round.dealer.first_card().get_rank() == Rank::Ace {
+            self.offer_insurance();
+        }
+        // Nobody acts after surrendering early or taking even money, against a
+        // dealer blackjack found by peeking, or on a natural
+        let resolved = self.current_round.hands[0].outcome.is_some();
+        if resolved || self.dealer_has_peeked_blackjack() || self.is_blackjack(0) {
+            hand_index = self.current_round.hands.len();
+        }
         while hand_index < self.current_round.hands.len() {
+            if self.current_round.hands[hand_index].cards.len() == 1 {
+                self.deal_split_card(hand_index);
+            }
             play = BasicStrategyLUT::make_move(self, hand_index);
             match play {
                 Decision::Hit => {
@@ -160,98 +419,325 @@ impl Game {
                     self.split(hand_index);
                 }
                 Decision::Surrender => {
-                    self.surrender();
+                    self.surrender(hand_index);
                     hand_index += 1;
-                    surrendered = true;
                     continue;
                 }
                 _ => unreachable!(),
             }
         }
-        self.count.update(self.current_round.dealer.dealer_play(&mut self.current_round.shoe, self.rules.hit_soft_17));
-        for hand_index in 0..self.current_round.hands.len() {
-            if surrendered {
-                self.surrendered(hand_index);
-            } else if self.is_bust(hand_index) {
-                self.loss(hand_index);
-            } else if self.is_push(hand_index) {
-                self.push(hand_index);
-            } else if self.is_winner(hand_index) {
-                self.award_winnings(hand_index);
-            } else {
-                self.loss(hand_index);
+        self.settle_round();
+        self.audit_cards();
+        self.discard_round();
+        match self.rules.shuffling {
+            Shuffling::CutCard => {
+                if self.should_reshuffle() {
+                    self.reshuffle();
+                }
+            }
+            // The machine also takes the discards back early if the shoe runs low
+            Shuffling::Continuous { batch, reserve } => {
+                if self.results.rounds.is_multiple_of(batch.max(1)) || self.should_reshuffle() {
+                    self.return_discards(reserve);
+                }
             }
         }
-        if self.should_reshuffle() {
-            self.reshuffle();
+        self.audit_cards();
+    }
+
+    // The dealer plays out their hand and every bet is settled against it
+    #[inline(always)]
+    pub fn settle_round(&mut self) {
+        self.dealer_play();
+        self.settle_insurance();
+        for hand_index in 0..self.current_round.hands.len() {
+            let outcome = self.outcome(hand_index);
+            self.current_round.hands[hand_index].outcome = Some(outcome);
+            self.settle(hand_index, outcome);
+        }
+    }
+
+    // The dealer only draws while a hand is still standing against them. Without a
+    // hole card, a natural, an insurance bet or a doubled or split bet that
+    // original-bets-only would return still needs a second card to check for
+    // blackjack, which only an ace or a ten can make. Then every card left face
+    // down is turned over and counted
+    #[inline(always)]
+    pub fn dealer_play(&mut self) {
+        let round = &mut self.current_round;
+        let can_have_blackjack = round.dealer.first_card().value() >= 10;
+        let extra_bets = round.hands.len() > 1 || round.hands[0].bet != round.initial_bet;
+        let returnable = self.rules.hole_card == HoleCard::OriginalBetsOnly && extra_bets;
+        let unsettled = returnable || round.hands.iter().any(|hand| hand.outcome.is_none());
+        let live = round
+            .hands
+            .iter()
+            .any(|hand| hand.outcome.is_none() && !hand.is_blackjack());
+        if live {
+            round.dealer.dealer_play(&mut round.shoe, self.rules.hit_soft_17);
+        } else if round.dealer.len() == 1
+            && can_have_blackjack
+            && (unsettled || round.insurance != Money::ZERO)
+        {
+            round.dealer.hit(&mut round.shoe);
         }
+        self.count.update_all(&self.current_round.dealer.cards()[1..]);
+        self.turn_over_player_cards();
+    }
 
+    #[inline(always)]
+    pub fn should_insure(&self) -> bool {
+        match self.insurance {
+            InsuranceStrategy::Never => false,
+            InsuranceStrategy::IndexPlays => match &self.deviations {
+                Some(deviations) => deviations.take_insurance(self.true_count()),
+                None => false,
+            },
+            InsuranceStrategy::TenDensity => self.count.tens_rich(self.rules.decks_in_shoe),
+        }
+    }
+
+    // Insurance for half the bet against a dealer ace. Insuring a natural is the
+    // same as taking even money, which pays 1:1 whatever the dealer has
+    #[inline(always)]
+    pub fn offer_insurance(&mut self) {
+        if !self.should_insure() {
+            return;
+        }
+        if self.is_blackjack(0) {
+            self.results.even_money += 1;
+            self.current_round.hands[0].outcome = Some(HandOutcome::Win);
+            return;
+        }
+        let insurance = self.current_round.hands[0].bet.half(self.rules.payout_rounding);
+        self.results.insurances += 1;
+        self.results.amount_bet += insurance;
+        self.bankroll -= insurance;
+        self.current_round.insurance = insurance;
+    }
+
+    // Insurance pays 2:1 on a dealer blackjack
+    #[inline(always)]
+    pub fn settle_insurance(&mut self) {
+        let insurance = self.current_round.insurance;
+        if insurance == Money::ZERO {
+            return;
+        }
+        if self.current_round.dealer.is_blackjack() {
+            self.results.amount_won += insurance * 2;
+            self.bankroll += insurance * 3;
+        } else {
+            self.results.amount_lost += insurance;
+        }
+    }
+
+    // How the hand ends against the dealer's final hand, unless it already ended
+    // by surrendering, busting or taking even money. A bust against a dealer
+    // natural is settled like any other loss, so original-bets-only gives back
+    // what was doubled or split
+    #[inline(always)]
+    pub fn outcome(&self, hand_index: usize) -> HandOutcome {
+        let hand = &self.current_round.hands[hand_index];
+        let dealer = &self.current_round.dealer;
+        let original_bets_only = self.rules.hole_card == HoleCard::OriginalBetsOnly;
+        match hand.outcome {
+            Some(HandOutcome::Busted) if original_bets_only && dealer.is_blackjack() => {}
+            Some(outcome) => return outcome,
+            None => {}
+        }
+        let dealer_value = dealer.num_value();
+        let player_value = hand.cards.num_value();
+        if dealer.is_blackjack() {
+            // Split hands are only extra bets, which original-bets-only returns
+            let returned = original_bets_only && hand_index > 0;
+            match hand.is_blackjack() || returned {
+                true => HandOutcome::Push,
+                false => HandOutcome::Loss,
+            }
+        } else if hand.is_blackjack() {
+            HandOutcome::Blackjack
+        } else if dealer_value > 21 || player_value > dealer_value {
+            HandOutcome::Win
+        } else if player_value == dealer_value {
+            HandOutcome::Push
+        } else {
+            HandOutcome::Loss
+        }
+    }
+
+    #[inline(always)]
+    pub fn settle(&mut self, hand_index: usize, outcome: HandOutcome) {
+        match outcome {
+            HandOutcome::Win => self.award_winnings(hand_index),
+            HandOutcome::Blackjack => self.award_blackjack(hand_index),
+            HandOutcome::Push => self.push(hand_index),
+            HandOutcome::Loss => {
+                let original_bets_only = self.rules.hole_card == HoleCard::OriginalBetsOnly;
+                if original_bets_only && self.current_round.dealer.is_blackjack() {
+                    self.original_bet_loss(hand_index);
+                } else {
+                    self.loss(hand_index);
+                }
+            }
+            HandOutcome::Busted => self.loss(hand_index),
+            HandOutcome::Surrendered => self.surrendered(hand_index),
+        }
     }
 
     #[inline(always)]
     pub fn split(&mut self, hand_index: usize) {
-        self.splits += 1;
+        self.results.splits += 1;
+        self.turn_over_player_cards();
         self.current_round.split(hand_index);
-        self.count.update(self.current_round.hands[hand_index].cards.cards[1].hilo_value());
-        self.count.update(self.current_round.hands[hand_index + 1].cards.cards[1].hilo_value());
+        self.count.update(self.current_round.hands[hand_index].cards.cards()[1]);
         self.bankroll -= self.current_round.hands[hand_index].bet;
+        self.results.amount_bet += self.current_round.hands[hand_index].bet;
+    }
+
+    // A split hand only gets its second card once the hands before it are
+    // finished, so the count doesn't see it until then
+    #[inline(always)]
+    pub fn deal_split_card(&mut self, hand_index: usize) {
+        self.count.update(self.current_round.deal_split_card(hand_index));
     }
 
     #[inline(always)]
     pub fn double(&mut self, hand_index: usize) {
-        self.doubles += 1;
-        self.count.update(self.current_round.double(hand_index));
-        self.bankroll -= self.current_round.hands[hand_index].bet >> 1;
+        let bet = self.current_round.hands[hand_index].bet;
+        self.results.doubles += 1;
+        self.turn_over_player_cards();
+        let card = self.current_round.double(hand_index);
+        self.see_player_card(card);
+        self.bankroll -= bet;
+        self.results.amount_bet += bet;
     }
 
     #[inline(always)]
     pub fn hit(&mut self, hand_index: usize) {
-        self.hits += 1;
+        self.results.hits += 1;
         self.count.update(self.current_round.hit(hand_index));
+        if self.is_bust(hand_index) {
+            self.turn_over_player_cards();
+        }
     }
 
     #[inline(always)]
-    pub fn surrender(&mut self) {
-        self.surrenders += 1;
-        self.bankroll += self.current_round.hands[0].bet >> 1;
+    pub fn surrender(&mut self, hand_index: usize) {
+        let hand = &mut self.current_round.hands[hand_index];
+        hand.outcome = Some(HandOutcome::Surrendered);
+        self.results.surrenders += 1;
+        self.bankroll += hand.bet.half(self.rules.payout_rounding);
+        self.turn_over_player_cards();
+    }
+
+    // Counts a player card straight away, or once the hand is turned over when
+    // it's dealt face down
+    #[inline(always)]
+    pub fn see_player_card(&mut self, card: Card) {
+        match self.rules.face_down_dealing {
+            true => self.current_round.face_down.push(card),
+            false => self.count.update(card),
+        }
+    }
+
+    #[inline(always)]
+    pub fn turn_over_player_cards(&mut self) {
+        self.count.update_all(&self.current_round.face_down);
+        self.current_round.face_down.clear();
     }
 
     #[inline(always)]
     pub fn stand(&mut self) {
-        self.stands += 1;
+        self.results.stands += 1;
     }
 
     #[inline(always)]
-    pub fn should_reshuffle(&mut self) -> bool {
-        let max_penetration = self.rules.deck_penetration;
-        let cards_left = self.current_round.shoe.cards_left();
+    pub fn true_count(&self) -> isize {
+        let shoe = &self.current_round.shoe;
+        self.count
+            .true_count(shoe.cards_left(), shoe.discards().len(), self.rules.decks_in_shoe)
+    }
 
-        cards_left <= max_penetration
+    // The round the cut card comes out in is finished before the shuffle. A shoe
+    // that ran out mid-round is reshuffled as soon as that round is over
+    #[inline(always)]
+    pub fn should_reshuffle(&mut self) -> bool {
+        let shoe = &self.current_round.shoe;
+        shoe.cut_card_out() || shoe.ran_out()
     }
 
+    // The discard tray goes back in with the rest of the shoe
     #[inline(always)]
     pub fn reshuffle(&mut self) {
-        self.current_round.shoe = Shoe::new(self.rules.decks_in_shoe);
-        self.count.reset();
+        match &self.rules.hand_shuffle {
+            Some(procedure) => self.current_round.shoe.hand_shuffle(procedure, &mut self.rng),
+            None => self.current_round.shoe.reshuffle(&mut self.rng),
+        }
+        Self::place_cut_card(&self.rules, &mut self.current_round.shoe, &mut self.rng);
+        self.count.reset(self.rules.decks_in_shoe);
     }
 
+    // Every card of the round goes to the discard tray once it's settled
     #[inline(always)]
-    pub fn is_bust(&mut self, hand_index: usize) -> bool {
-        self.current_round.hands[hand_index].cards.is_bust()
+    pub fn discard_round(&mut self) {
+        let round = &mut self.current_round;
+        for hand in round.hands.drain(..) {
+            round.shoe.discard(hand.cards.cards());
+        }
+        let dealer = std::mem::take(&mut round.dealer);
+        round.shoe.discard(dealer.cards());
+    }
+
+    // When auditing, checks that the shoe, the discard tray and the cards in play
+    // still make up the decks the shoe started with
+    #[inline(always)]
+    pub fn audit_cards(&self) {
+        if !self.audit {
+            return;
+        }
+        let round = &self.current_round;
+        let mut in_play: SmallVec<[Card; 16]> = smallvec![];
+        for hand in round.hands.iter() {
+            in_play.extend_from_slice(hand.cards.cards());
+        }
+        in_play.extend_from_slice(round.dealer.cards());
+        round.shoe.audit(&in_play);
+    }
+
+    // Shuffles the discards back into a continuous shuffler. Once the cards are
+    // back in the machine the count no longer says anything about what's left
+    #[inline(always)]
+    pub fn return_discards(&mut self, reserve: usize) {
+        self.current_round.shoe.shuffle_in_discards(reserve, &mut self.rng);
+        self.count.reset(self.rules.decks_in_shoe);
     }
 
     #[inline(always)]
-    pub fn is_winner(&mut self, hand_index: usize) -> bool {
-        let dealer_value = self.current_round.dealer.num_value();
-        let player_value = self.current_round.hands[hand_index].cards.num_value();
-        (player_value > dealer_value) || (dealer_value > 21)
+    fn new_shoe(rules: &GameRules, rng: &mut StdRng) -> Shoe {
+        let mut shoe = Shoe::new(rules.decks_in_shoe, rng);
+        Self::place_cut_card(rules, &mut shoe, rng);
+        shoe
     }
 
+    // Places the cut card at random around the penetration point, always leaving
+    // enough cards behind it to finish a round. A continuous shuffler has no cut
+    // card, it only keeps enough cards back
     #[inline(always)]
-    pub fn is_push(&mut self, hand_index: usize) -> bool {
-        let dealer_value = self.current_round.dealer.num_value();
-        let player_value = self.current_round.hands[hand_index].cards.num_value();
-        (dealer_value == player_value) && !(dealer_value > 21)
+    fn place_cut_card(rules: &GameRules, shoe: &mut Shoe, rng: &mut StdRng) {
+        if let Shuffling::Continuous { .. } = rules.shuffling {
+            shoe.place_cut_card(MIN_CARDS_BEHIND_CUT);
+            return;
+        }
+        let cards = shoe.cards_left();
+        let spread = rules.cut_card_spread;
+        let dealt = rules.penetration.cards_dealt(rules.decks_in_shoe) + spread;
+        let dealt = rng.gen_range(dealt.saturating_sub(2 * spread)..=dealt);
+        shoe.place_cut_card(cards.saturating_sub(dealt).max(MIN_CARDS_BEHIND_CUT));
+    }
+
+    #[inline(always)]
+    pub fn is_bust(&mut self, hand_index: usize) -> bool {
+        self.current_round.hands[hand_index].cards.is_bust()
     }
 
     #[inline(always)]
@@ -259,48 +745,97 @@ impl Game {
         self.current_round.hands[hand_index].is_blackjack()
     }
 
+    // Early surrender comes before the dealer checks for blackjack, and isn't
+    // offered on a natural
+    #[inline(always)]
+    pub fn can_surrender_early(&mut self) -> bool {
+        self.rules.surrender == Some(Surrender::Early) && !self.is_blackjack(0)
+    }
+
+    #[inline(always)]
+    pub fn dealer_has_peeked_blackjack(&self) -> bool {
+        self.rules.hole_card == HoleCard::Peek && self.current_round.dealer.is_blackjack()
+    }
+
     #[inline(always)]
     pub fn award_winnings(&mut self, hand_index: usize) {
-        let is_blackjack = self.is_blackjack(hand_index);
         let bet = self.current_round.hands[hand_index].bet;
-        self.wins += 1;
-        self.amount_won += bet;
-        self.bankroll += bet << 1;
-        if is_blackjack {
-            self.blackjacks += 1;
-            self.amount_won += bet >> 1;
-            self.bankroll += bet >> 1;
-        }
+        self.results.wins += 1;
+        self.results.amount_won += bet;
+        self.bankroll += bet + bet;
+    }
+
+    #[inline(always)]
+    pub fn award_blackjack(&mut self, hand_index: usize) {
+        let bet = self.current_round.hands[hand_index].bet;
+        let winnings = bet.scale(self.rules.blackjack_payout, self.rules.payout_rounding);
+        self.results.wins += 1;
+        self.results.blackjacks += 1;
+        self.results.amount_won += winnings;
+        self.bankroll += bet + winnings;
     }
     #[inline(always)]
     pub fn loss(&mut self, hand_index: usize) {
-        self.losses += 1;
-        self.amount_lost += self.current_round.hands[hand_index].bet;
+        self.results.losses += 1;
+        self.results.amount_lost += self.current_round.hands[hand_index].bet;
+    }
+
+    // Only the original bet is lost, anything added by doubling is returned
+    #[inline(always)]
+    pub fn original_bet_loss(&mut self, hand_index: usize) {
+        let bet = self.current_round.hands[hand_index].bet;
+        let original_bet = self.current_round.initial_bet;
+        self.results.losses += 1;
+        self.results.amount_lost += original_bet;
+        self.bankroll += bet - original_bet;
     }
 
     pub fn surrendered(&mut self, hand_index: usize) {
-        self.losses += 1;
-        self.amount_lost += self.current_round.hands[hand_index].bet >> 1;
+        self.results.losses += 1;
+        let bet = self.current_round.hands[hand_index].bet;
+        self.results.amount_lost += bet - bet.half(self.rules.payout_rounding);
     }
 
     #[inline(always)]
     pub fn push(&mut self, hand_index: usize) {
         let bet = self.current_round.hands[hand_index].bet;
-        self.pushes += 1;
+        self.results.pushes += 1;
         self.bankroll += bet;
     }
 
 
     #[inline(always)]
     fn new_round(&mut self) {
-        self.bankroll -= self.count.std_bet;
-        let (hand, hi_lo_value) = Hand::new(self.count.std_bet, &mut self.current_round.shoe);
-        self.count.update(hi_lo_value);
-        self.current_round.hands = smallvec![hand];
-        let (dealer, _) = HandCards::from(&mut self.current_round.shoe);
-        self.count.update(dealer.first_card().hilo_value());
+        let bet = self.betting.bet(self.true_count());
+        self.bankroll -= bet;
+        self.results.initial_bet += bet;
+        self.results.amount_bet += bet;
+        let mut cards = HandCards::new();
+        let mut dealer = HandCards::new();
+        for deal in DEALING_ORDER {
+            match deal {
+                Deal::Player => {
+                    let card = self.current_round.shoe.deal();
+                    cards.push(card);
+                    self.see_player_card(card);
+                }
+                Deal::DealerUpcard => {
+                    let card = self.current_round.shoe.deal();
+                    dealer.push(card);
+                    self.count.update(card);
+                }
+                // Without a hole card the dealer's second card is drawn with the rest
+                Deal::DealerHoleCard => {
+                    if self.rules.hole_card == HoleCard::Peek {
+                        dealer.push(self.current_round.shoe.deal());
+                    }
+                }
+            }
+        }
+        self.current_round.hands = smallvec![Hand::new(bet, cards)];
+        self.current_round.initial_bet = bet;
+        self.current_round.insurance = Money::ZERO;
         self.current_round.dealer = dealer;
-        self.current_round.has_split_aces = false;
     }
 }
 
@@ -308,7 +843,11 @@ impl Game {
 pub struct Round {
     pub rules: GameRules,
     pub shoe: Shoe,
-    pub has_split_aces: bool,
+    pub initial_bet: Money,
+    // Insurance side bet, zero when not insured
+    pub insurance: Money,
+    // Player cards dealt face down that the count hasn't seen yet
+    pub face_down: SmallVec<[Card; 4]>,
     pub hands: SmallVec<[Hand; 4]>,
     pub dealer: HandCards,
 }
@@ -316,11 +855,23 @@ pub struct Round {
 impl Round {
     #[inline(always)]
     pub fn can_split(&self, hand_to_split: usize) -> bool {
-        let splits = &self.hands.len() - 1;
         let hand = &self.hands[hand_to_split];
-        let pair_of = hand.cards.first_card().get_rank();
-
-        !((!hand.cards.is_pair()) || (!self.rules.resplit_aces && self.has_split_aces && pair_of == Rank::Ace) || (splits >= 3))
+        let is_pair = match self.rules.split_unlike_tens {
+            true => hand.cards.is_value_pair(),
+            false => hand.cards.is_pair(),
+        };
+        if !is_pair {
+            return false;
+        }
+        let max_hands = match hand.cards.first_card().get_rank() {
+            Rank::Ace if !self.rules.resplit_aces => Some(2),
+            Rank::Ace => self.rules.max_ace_hands,
+            _ => self.rules.max_hands,
+        };
+        match max_hands {
+            Some(max_hands) => self.hands.len() < max_hands,
+            None => true,
+        }
     }
     #[inline(always)]
     pub fn can_double(&self, hand_to_double: usize) -> bool {
@@ -328,16 +879,18 @@ impl Round {
         let splits = &self.hands.len() - 1;
         let double_after_split = self.rules.double_after_split;
         let double_split_aces = self.rules.double_split_aces;
+        let doubling = self.rules.doubling;
 
-        !((hand.cards.len() != 2) || (!double_split_aces && hand.split_from == Some(Rank::Ace)) || (!double_after_split && (splits > 0)))
+        !((!doubling.allows(hand.cards.len(), hand.cards.num_value())) || (!double_split_aces && hand.split_from == Some(Rank::Ace)) || (!double_after_split && (splits > 0)))
     }
+    // Late surrender, once the dealer has checked for blackjack
     #[inline(always)]
     pub fn can_surrender(&self) -> bool {
         let splits = &self.hands.len() - 1;
         let hand_len = self.hands[0].cards.len();
-        let surrender = self.rules.surrender;
-        !(splits != 0 || hand_len > 2 || Option::is_none(&surrender))
+        !(splits != 0 || hand_len > 2 || !self.rules.late_surrender())
     }
+    #[allow(clippy::nonminimal_bool)]
     #[inline(always)]
     pub fn can_hit(&self, hand_to_hit: usize) -> bool {
         let hand = &self.hands[hand_to_hit];
@@ -351,53 +904,68 @@ impl Round {
         !(splits != 0 && !hit_split_aces)
     }
 
+    // The new hand is appended, so it's played after the existing hands
     #[inline(always)]
     pub fn split(&mut self, hand_index: usize) {
-        let card = self.hands[hand_index].cards.first_card();
         let new_hand = self.hands[hand_index].split(&mut self.shoe);
-        self.has_split_aces = card.get_rank() == Rank::Ace;
         self.hands.push(new_hand);
     }
 
     #[inline(always)]
-    pub fn double(&mut self, hand_index: usize) -> isize{
+    pub fn deal_split_card(&mut self, hand_index: usize) -> Card {
+        let card = self.shoe.deal();
+        self.hands[hand_index].cards.push(card);
+        card
+    }
+
+    #[inline(always)]
+    pub fn double(&mut self, hand_index: usize) -> Card {
         let hand = &mut self.hands[hand_index];
         hand.double(&mut self.shoe)
     }
     #[inline(always)]
-    pub fn hit(&mut self, hand_index: usize) -> isize {
+    pub fn hit(&mut self, hand_index: usize) -> Card {
         let hand = &mut self.hands[hand_index];
         hand.hit(&mut self.shoe)
     }
 }
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HandOutcome {
+    Win,
+    Loss,
+    Push,
+    Blackjack,
+    Surrendered,
+    Busted,
+}
+
 #[derive(Debug, Clone)]
 pub struct Hand {
     pub split_from: Option<Rank>,
-    pub bet: usize,
+    pub bet: Money,
     pub cards: HandCards,
+    // Set once the hand is settled, or as soon as it busts or surrenders
+    pub outcome: Option<HandOutcome>,
 }
 
 impl Hand {
     #[inline(always)]
-    pub fn new(bet: usize, shoe: &mut Shoe) -> (Self, isize) {
-        let (hand_cards, hi_lo_value) = HandCards::from(shoe);
-        (
-            Hand {
-                bet,
-                split_from: None,
-                cards: hand_cards,
-            },
-            hi_lo_value,
-        )
+    pub fn new(bet: Money, cards: HandCards) -> Self {
+        Hand {
+            bet,
+            split_from: None,
+            cards,
+            outcome: None,
+        }
     }
 
+    // The new hand keeps just the split card until it's played
     #[inline(always)]
     pub fn split(&mut self, shoe: &mut Shoe) -> Hand {
         let card = self.cards.pop();
         let card_rank = card.get_rank();
         let mut hand = HandCards::new();
         hand.push(card);
-        hand.push(shoe.deal());
 
         self.cards.push(shoe.deal());
         self.split_from = Some(card_rank);
@@ -406,18 +974,23 @@ impl Hand {
             bet: self.bet,
             split_from: Some(card_rank),
             cards: hand,
+            outcome: None,
         }
     }
 
     #[inline(always)]
-    pub fn hit(&mut self, shoe: &mut Shoe) -> isize {
-        self.cards.hit(shoe)
+    pub fn hit(&mut self, shoe: &mut Shoe) -> Card {
+        let card = self.cards.hit(shoe);
+        if self.is_bust() {
+            self.outcome = Some(HandOutcome::Busted);
+        }
+        card
     }
 
     #[inline(always)]
-    pub fn double(&mut self, shoe: &mut Shoe) -> isize {
-        self.bet <<= 1;
-        self.cards.hit(shoe)
+    pub fn double(&mut self, shoe: &mut Shoe) -> Card {
+        self.bet = self.bet * 2;
+        self.hit(shoe)
     }
 
     #[inline(always)]
@@ -430,3 +1003,279 @@ impl Hand {
         self.split_from.is_none() && self.cards.len() == 2 && self.cards.num_value() == 21
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A six deck S17 DAS game whose next round is dealt from the given cards,
+    // with the initial deal in DEALING_ORDER
+    fn scripted_game(cards: &str) -> Game {
+        let rules = GameRules::new(6, Penetration::Fraction(0.75), false, true, None, false, false, false);
+        scripted_game_with(rules, cards)
+    }
+
+    fn scripted_game_with(rules: GameRules, cards: &str) -> Game {
+        let mut game = Game::new(rules, Money::from_dollars(10_000), 1, 1);
+        game.current_round.shoe = Shoe::parse(cards).unwrap();
+        game.audit = true;
+        game
+    }
+
+    #[test]
+    fn splitting_eights_three_times_against_a_six() {
+        // 8,8 splits into 8,8 and 8,8, and the first hand splits again and doubles
+        // on 11. The second splits too, doubling on 10 and drawing an ace to 21,
+        // then the split hands play in the order they were made, each getting its
+        // second card when it comes into play. Dealer 16 busts
+        let mut game = scripted_game("8s 6h 8d Tc 8h 3s Td 8c 2c Ah 2s 9c Kd Qs 2h");
+        game.play_round();
+        assert_eq!(game.results.splits, 3);
+        assert_eq!(game.results.doubles, 3);
+        assert_eq!(game.results.wins, 4);
+        assert_eq!(game.results.net(), Money::from_dollars(700));
+        assert_eq!(game.bankroll, Money::from_dollars(10_700));
+        let shoe = &game.current_round.shoe;
+        assert_eq!(
+            format_cards(shoe.discards()),
+            "8s 3s Td 8d 2c Ah 8h 2s 9c 8c Kd 6h Tc Qs"
+        );
+        assert_eq!(shoe.to_string(), "2h");
+    }
+
+    #[test]
+    fn games_with_the_same_seed_play_the_same() {
+        let play = |seed| {
+            let rules = GameRules::new(6, Penetration::Fraction(0.75), false, true, None, false, false, false);
+            let mut game: Game = Game::new(rules, Money::from_dollars(100_000), 2_000, seed);
+            game.deviations = Some(IndexPlays::illustrious_18());
+            game.insurance = InsuranceStrategy::IndexPlays;
+            game.play()
+        };
+        let (first, second) = (play(7), play(7));
+        assert_eq!(first.results.rounds, 2_000);
+        assert_eq!(first.results, second.results);
+        assert_eq!(first.bankroll, second.bankroll);
+        assert_ne!(first.results, play(8).results);
+    }
+
+    #[test]
+    fn running_out_mid_round_shuffles_the_discards_back_in() {
+        // 9 against a 7 hits, with nothing left in the shoe but a ten in the tray
+        let mut game = scripted_game("Ts 5s 7h 4d Tc");
+        let ten = game.current_round.shoe.deal();
+        game.current_round.shoe.discard(&[ten]);
+        game.play_round();
+        assert_eq!(game.results.hits, 1);
+        assert_eq!(game.results.net(), Money::from_dollars(100));
+        // Everything goes back in for a fresh shuffle once the round is over
+        let shoe = &game.current_round.shoe;
+        assert!(!shoe.ran_out());
+        assert_eq!(shoe.cards_left(), 5);
+        assert!(shoe.discards().is_empty());
+    }
+
+    #[test]
+    fn late_surrender_needs_a_peek() {
+        // 16 against a 9 is surrendered late when the dealer peeks, but hit when
+        // there's no hole card, because a dealer natural would still take it all
+        let mut rules = GameRules::new(6, Penetration::Fraction(0.75), false, true, None, false, false, false);
+        rules.surrender = Some(Surrender::Late);
+        let mut game = scripted_game_with(rules.clone(), "Ts 9h 6d 2c 5c");
+        game.play_round();
+        assert_eq!(game.results.surrenders, 1);
+        assert_eq!(game.results.net(), Money::from_dollars(-50));
+        rules.hole_card = HoleCard::NoHoleCard;
+        let mut game = scripted_game_with(rules, "Ts 9h 6d 5c Ah");
+        game.play_round();
+        assert_eq!(game.results.surrenders, 0);
+        assert_eq!(game.results.hits, 1);
+        assert_eq!(game.results.net(), Money::from_dollars(100));
+    }
+
+    #[test]
+    fn index_play_to_hit_cancels_the_tables_surrender() {
+        // 15 against a ten is surrendered by the table, and by the Fab 4 only at 0 or above
+        let mut rules = GameRules::new(6, Penetration::Fraction(0.75), false, true, None, false, false, false);
+        rules.surrender = Some(Surrender::Late);
+        let mut game = scripted_game_with(rules, "Ts Td 5h 9c 2d");
+        game.new_round();
+        assert_eq!(BasicStrategyLUT::make_move(&game, 0), Decision::Surrender);
+        game.deviations = Some(IndexPlays::illustrious_18());
+        game.count.running_count = -1;
+        assert!(game.true_count() < 0);
+        assert_eq!(BasicStrategyLUT::make_move(&game, 0), Decision::Hit);
+        game.count.running_count = 0;
+        assert_eq!(game.true_count(), 0);
+        assert_eq!(BasicStrategyLUT::make_move(&game, 0), Decision::Surrender);
+    }
+
+    // A game that insures by the Illustrious 18 index, with the count well above it
+    fn insuring_game(rules: GameRules, cards: &str) -> Game {
+        let mut game = scripted_game_with(rules, cards);
+        game.deviations = Some(IndexPlays::illustrious_18());
+        game.insurance = InsuranceStrategy::IndexPlays;
+        game.count.running_count = 10;
+        game
+    }
+
+    #[test]
+    fn insurance_pays_two_to_one_on_a_dealer_blackjack() {
+        // 20 against an ace is insured for $50 and loses $100 to the natural
+        let mut game = insuring_game(GameRules::new(6, Penetration::Fraction(0.75), false, true, None, false, false, false), "Ts Ah Td Kc");
+        game.play_round();
+        assert_eq!(game.results.insurances, 1);
+        assert_eq!(game.results.losses, 1);
+        assert_eq!(game.results.net(), Money::ZERO);
+        assert_eq!(game.bankroll, Money::from_dollars(10_000));
+    }
+
+    #[test]
+    fn insurance_is_lost_when_the_dealer_has_no_blackjack() {
+        // 20 against an ace is insured for $50 and beats the dealer's 18
+        let mut game = insuring_game(GameRules::new(6, Penetration::Fraction(0.75), false, true, None, false, false, false), "Ts Ah Td 7c");
+        game.play_round();
+        assert_eq!(game.results.insurances, 1);
+        assert_eq!(game.results.net(), Money::from_dollars(50));
+        assert_eq!(game.bankroll, Money::from_dollars(10_050));
+    }
+
+    #[test]
+    fn even_money_pays_one_to_one_against_a_dealer_blackjack() {
+        let mut game = insuring_game(GameRules::new(6, Penetration::Fraction(0.75), false, true, None, false, false, false), "As Ah Kd Kc");
+        game.play_round();
+        assert_eq!(game.results.even_money, 1);
+        assert_eq!(game.results.insurances, 0);
+        assert_eq!(game.results.net(), Money::from_dollars(100));
+    }
+
+    #[test]
+    fn insurance_without_a_hole_card_waits_for_the_dealers_second_card() {
+        // 20 stands against the ace, then the dealer draws a ten for blackjack
+        let mut rules = GameRules::new(6, Penetration::Fraction(0.75), false, true, None, false, false, false);
+        rules.hole_card = HoleCard::NoHoleCard;
+        let mut game = insuring_game(rules, "Ts Ah Td Kc");
+        game.play_round();
+        assert_eq!(game.results.insurances, 1);
+        assert_eq!(game.results.losses, 1);
+        assert_eq!(game.results.net(), Money::ZERO);
+    }
+
+    fn hi_lo(estimation: DeckEstimation, rounding: TrueCountRounding, running_count: isize) -> Count {
+        let mut count = Count::<HiLo>::with_precision(6, estimation, rounding);
+        count.running_count = running_count;
+        count
+    }
+
+    #[test]
+    fn exact_estimation_divides_by_the_cards_left() {
+        let count = hi_lo(DeckEstimation::Exact, TrueCountRounding::Floor, 5);
+        assert_eq!(count.decks_remaining(130, 182, 6), 2.5);
+        assert_eq!(count.decks_remaining(100, 212, 6), 100.0 / 52.0);
+        assert_eq!(count.raw_true_count(130, 182, 6), 2.0);
+    }
+
+    #[test]
+    fn half_deck_estimation_rounds_to_the_nearest_half_deck() {
+        let count = hi_lo(DeckEstimation::HalfDeck, TrueCountRounding::Floor, 5);
+        assert_eq!(count.decks_remaining(130, 182, 6), 2.5);
+        assert_eq!(count.decks_remaining(100, 212, 6), 2.0);
+        assert_eq!(count.decks_remaining(5, 307, 6), 0.5);
+        assert_eq!(count.raw_true_count(100, 212, 6), 2.5);
+    }
+
+    #[test]
+    fn full_deck_estimation_rounds_to_the_nearest_deck() {
+        let count = hi_lo(DeckEstimation::FullDeck, TrueCountRounding::Floor, 5);
+        assert_eq!(count.decks_remaining(130, 182, 6), 3.0);
+        assert_eq!(count.decks_remaining(100, 212, 6), 2.0);
+        assert_eq!(count.decks_remaining(5, 307, 6), 1.0);
+        assert_eq!(count.raw_true_count(130, 182, 6), 5.0 / 3.0);
+    }
+
+    #[test]
+    fn true_counts_round_as_configured() {
+        // 5 and -5 over two decks left, whose true counts fall halfway between
+        let cases = [
+            (TrueCountRounding::Floor, 2, -3),
+            (TrueCountRounding::Truncate, 2, -2),
+            (TrueCountRounding::Round, 3, -3),
+        ];
+        for (rounding, positive, negative) in cases {
+            let count = hi_lo(DeckEstimation::HalfDeck, rounding, 5);
+            assert_eq!(count.true_count(100, 212, 6), positive, "{:?}", rounding);
+            let count = hi_lo(DeckEstimation::HalfDeck, rounding, -5);
+            assert_eq!(count.true_count(100, 212, 6), negative, "{:?}", rounding);
+        }
+    }
+
+    #[test]
+    fn unbalanced_counts_key_off_the_running_count() {
+        let mut knock_out = Count::<KnockOut>::new(6);
+        let mut hi_lo = Count::<HiLo>::new(6);
+        for card in parse_cards("2s 3h 4d 5c 6s 7h").unwrap() {
+            knock_out.update(card);
+            hi_lo.update(card);
+        }
+        // KO starts at -20 in six decks and counts the seven, Hi-Lo is +5 over
+        // three decks left
+        assert_eq!(knock_out.true_count(156, 150, 6), -14);
+        assert_eq!(hi_lo.true_count(156, 150, 6), 1);
+    }
+
+    #[test]
+    fn busted_double_only_loses_the_original_bet_to_a_natural() {
+        // 16 doubled against an ace busts, and the dealer turns up a ten
+        let mut rules = GameRules::new(6, Penetration::Fraction(0.75), false, true, None, false, false, false);
+        rules.hole_card = HoleCard::OriginalBetsOnly;
+        let mut game = scripted_game_with(rules, "Ts Ah 6d Kc Td 2h");
+        game.new_round();
+        game.double(0);
+        game.settle_round();
+        assert_eq!(game.current_round.dealer.num_value(), 21);
+        assert_eq!(game.results.losses, 1);
+        assert_eq!(game.results.net(), Money::from_dollars(-100));
+    }
+
+    #[test]
+    fn busted_double_against_a_low_card_leaves_the_dealer_one_card() {
+        // 16 doubled against a six busts, and a six can't make a natural
+        let mut rules = GameRules::new(6, Penetration::Fraction(0.75), false, true, None, false, false, false);
+        rules.hole_card = HoleCard::OriginalBetsOnly;
+        let mut game = scripted_game_with(rules, "Ts 6h 6d Kc Td");
+        game.new_round();
+        game.double(0);
+        game.settle_round();
+        assert_eq!(game.current_round.dealer.len(), 1);
+        assert_eq!(game.results.net(), Money::from_dollars(-200));
+        assert_eq!(game.current_round.shoe.to_string(), "Td");
+    }
+
+    #[test]
+    fn busted_split_hand_is_returned_against_a_natural() {
+        // 8,8 against a ten splits, the first hand stands on 16 and the second
+        // busts, then the dealer draws an ace
+        let mut rules = GameRules::new(6, Penetration::Fraction(0.75), false, true, None, false, false, false);
+        rules.hole_card = HoleCard::OriginalBetsOnly;
+        let mut game = scripted_game_with(rules, "8s Th 8d 8c Tc 6s Ah 2h");
+        game.new_round();
+        game.split(0);
+        game.stand();
+        game.deal_split_card(1);
+        game.hit(1);
+        game.settle_round();
+        assert_eq!(game.current_round.dealer.num_value(), 21);
+        assert_eq!(game.results.losses, 1);
+        assert_eq!(game.results.pushes, 1);
+        assert_eq!(game.results.net(), Money::from_dollars(-100));
+    }
+
+    #[test]
+    fn natural_pays_three_to_two_without_the_dealer_drawing() {
+        let mut game = scripted_game("As 6h Kd Tc 2h");
+        game.play_round();
+        assert_eq!(game.results.blackjacks, 1);
+        assert_eq!(game.results.net(), Money::from_dollars(150));
+        assert_eq!(game.current_round.shoe.to_string(), "2h");
+    }
+}
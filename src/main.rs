@@ -1,36 +1,51 @@
+pub mod analysis;
 pub mod basicstrategy;
+pub mod betting;
 pub mod cardutils;
+pub mod counting;
+pub mod deviations;
 pub mod gamelogic;
+pub mod money;
+pub mod parsing;
+pub mod results;
+pub mod shuffle;
+pub mod strategygen;
 use smallvec::{SmallVec, smallvec};
 use std::thread::JoinHandle;
 use std::time::Instant;
 
 use gamelogic::*;
 use basicstrategy::*;
+use money::*;
+use counting::*;
+use results::*;
 //use cardutils::*;
 fn main() {
-    let mut thread_pool: SmallVec<[JoinHandle<()>; 11]> = smallvec![];
-    let bankroll = 1000000000000000;
+    let mut thread_pool: SmallVec<[JoinHandle<SimulationResults>; 11]> = smallvec![];
+    let bankroll = Money::from_dollars(1000000000000000);
     let num_games = 100_000_000;
-    let rules = GameRules::new(6, 47, false, true, None, false, false, false);
-    BasicStrategyLUT::calculate_cache(&Game::new(rules, bankroll, num_games / 12));
+    let seed: u64 = rand::random();
+    let rules = GameRules::new(6, Penetration::Fraction(0.85), false, true, None, false, false, false);
+    BasicStrategyLUT::calculate_cache(&Game::<HiLo>::new(rules, bankroll, num_games / 12, seed));
     println!("Simulating {} games of blackjack across 12 threads, 6 cores", num_games);
+    println!("Seed: {}", seed);
     let timer = Instant::now();
-    for _ in 0..11 {
-        let rules = GameRules::new(6, 47, false, true, None, false, false, false);
-        let game = Game::new(rules, bankroll, num_games / 12);
+    for thread_index in 0..11 {
+        let rules = GameRules::new(6, Penetration::Fraction(0.85), false, true, None, false, false, false);
+        let game = Game::<HiLo>::new(rules, bankroll, num_games / 12, seed.wrapping_add(thread_index + 1));
         thread_pool.push(std::thread::spawn(move || {
-            game.play();
+            game.play().results
         }));
     }
-    let rules = GameRules::new(6, 47, false, true, None, false, false, false);
-    let game = Game::new(rules, bankroll, num_games / 12);
+    let rules = GameRules::new(6, Penetration::Fraction(0.85), false, true, None, false, false, false);
+    let game = Game::<HiLo>::new(rules, bankroll, num_games / 12, seed);
 
-    game.play();
+    let mut results = game.play().results;
     for thread in thread_pool {
-        thread.join().unwrap();
+        results += &thread.join().unwrap();
     }
     let elapsed = timer.elapsed();
+    println!("{}", results);
     println!("Time taken: {} nanoseconds", elapsed.as_nanos());
     println!("Time taken: {} milliseconds", elapsed.as_millis());
     println!("Time taken: {} seconds", elapsed.as_secs());
@@ -38,8 +53,8 @@ fn main() {
 
     //let bankroll = 1000000000000000;
     //let num_games = 1000000000;
-    //let rules = GameRules::new(6, 47, false, true, None, false, false, false);
-    //let game = Game::new(rules, bankroll, num_games / 12);
+    //let rules = GameRules::new(6, Penetration::Fraction(0.85), false, true, None, false, false, false);
+    //let game = Game::<HiLo>::new(rules, bankroll, num_games / 12, seed);
     //let timer = Instant::now();
     //let game_results = game.play();
     //let elapsed = timer.elapsed();
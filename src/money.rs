@@ -0,0 +1,226 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+// Signed amount of money stored as whole cents. Arithmetic panics on overflow
+// instead of silently wrapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    #[inline(always)]
+    pub const fn from_cents(cents: i64) -> Self {
+        Money(cents)
+    }
+
+    #[inline(always)]
+    pub const fn from_dollars(dollars: i64) -> Self {
+        Money(dollars.checked_mul(100).expect("Money overflow"))
+    }
+
+    #[inline(always)]
+    pub fn cents(&self) -> i64 {
+        self.0
+    }
+
+    #[inline(always)]
+    pub fn as_dollars(&self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+
+    #[inline(always)]
+    pub fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+
+    // Multiplies by ratio.numerator / ratio.denominator, rounding whatever doesn't
+    // divide evenly according to the table's rounding rule
+    #[inline(always)]
+    pub fn scale(self, ratio: Ratio, rounding: PayoutRounding) -> Money {
+        let scaled = (self.0 as i128) * (ratio.numerator as i128);
+        rounding.round(scaled, ratio.denominator as i128)
+    }
+
+    #[inline(always)]
+    pub fn half(self, rounding: PayoutRounding) -> Money {
+        self.scale(Ratio::ONE_TO_TWO, rounding)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+    #[inline(always)]
+    fn add(self, other: Money) -> Money {
+        Money(self.0.checked_add(other.0).expect("Money overflow"))
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+    #[inline(always)]
+    fn sub(self, other: Money) -> Money {
+        Money(self.0.checked_sub(other.0).expect("Money overflow"))
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+    #[inline(always)]
+    fn neg(self) -> Money {
+        Money(self.0.checked_neg().expect("Money overflow"))
+    }
+}
+
+impl Mul<i64> for Money {
+    type Output = Money;
+    #[inline(always)]
+    fn mul(self, factor: i64) -> Money {
+        Money(self.0.checked_mul(factor).expect("Money overflow"))
+    }
+}
+
+impl AddAssign for Money {
+    #[inline(always)]
+    fn add_assign(&mut self, other: Money) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Money {
+    #[inline(always)]
+    fn sub_assign(&mut self, other: Money) {
+        *self = *self - other;
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let cents = self.0.unsigned_abs();
+        write!(f, "{}${}.{:02}", sign, cents / 100, cents % 100)
+    }
+}
+
+// A payout ratio such as 3:2 or 6:5
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ratio {
+    pub numerator: i64,
+    pub denominator: i64,
+}
+
+impl Ratio {
+    pub const ONE_TO_TWO: Ratio = Ratio::new(1, 2);
+    pub const THREE_TO_TWO: Ratio = Ratio::new(3, 2);
+    pub const SIX_TO_FIVE: Ratio = Ratio::new(6, 5);
+    pub const SEVEN_TO_FIVE: Ratio = Ratio::new(7, 5);
+    pub const EVEN_MONEY: Ratio = Ratio::new(1, 1);
+    pub const TWO_TO_ONE: Ratio = Ratio::new(2, 1);
+
+    #[inline(always)]
+    pub const fn new(numerator: i64, denominator: i64) -> Self {
+        assert!(denominator > 0, "Ratio denominator must be positive");
+        Ratio {
+            numerator,
+            denominator,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    // Toward negative infinity, in the house's favor for payouts
+    Down,
+    // Toward positive infinity, in the player's favor for payouts
+    Up,
+    // To the nearest increment, halves go up
+    Nearest,
+}
+
+// How the casino pays amounts that don't come out to a whole chip, e.g. 3:2 on
+// a $5 bet with only $1 chips in the rack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayoutRounding {
+    pub mode: RoundingMode,
+    pub increment: Money,
+}
+
+impl PayoutRounding {
+    #[inline(always)]
+    pub const fn new(mode: RoundingMode, increment: Money) -> Self {
+        assert!(increment.0 > 0, "Rounding increment must be positive");
+        PayoutRounding { mode, increment }
+    }
+
+    // Rounds numerator / denominator cents to a multiple of the increment
+    #[inline(always)]
+    fn round(&self, numerator: i128, denominator: i128) -> Money {
+        let denominator = denominator * self.increment.0 as i128;
+        let units = match self.mode {
+            RoundingMode::Down => numerator.div_euclid(denominator),
+            RoundingMode::Up => -(-numerator).div_euclid(denominator),
+            RoundingMode::Nearest => (2 * numerator + denominator).div_euclid(2 * denominator),
+        };
+        Money(i64::try_from(units * self.increment.0 as i128).expect("Money overflow"))
+    }
+}
+
+impl Default for PayoutRounding {
+    #[inline(always)]
+    fn default() -> Self {
+        PayoutRounding::new(RoundingMode::Down, Money::from_cents(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dollar_rounding(mode: RoundingMode) -> PayoutRounding {
+        PayoutRounding::new(mode, Money::from_dollars(1))
+    }
+
+    #[test]
+    fn three_to_two_on_an_odd_bet_rounds_to_whole_dollars() {
+        let bet = Money::from_dollars(5);
+        let pay = |mode| bet.scale(Ratio::THREE_TO_TWO, dollar_rounding(mode));
+        assert_eq!(pay(RoundingMode::Down), Money::from_dollars(7));
+        assert_eq!(pay(RoundingMode::Up), Money::from_dollars(8));
+        assert_eq!(pay(RoundingMode::Nearest), Money::from_dollars(8));
+    }
+
+    #[test]
+    fn six_to_five_on_odd_bets_rounds_to_whole_dollars() {
+        // $7 pays $8.40 and $9 pays $10.80
+        let pay = |dollars, mode| {
+            Money::from_dollars(dollars).scale(Ratio::SIX_TO_FIVE, dollar_rounding(mode))
+        };
+        assert_eq!(pay(7, RoundingMode::Down), Money::from_dollars(8));
+        assert_eq!(pay(7, RoundingMode::Up), Money::from_dollars(9));
+        assert_eq!(pay(7, RoundingMode::Nearest), Money::from_dollars(8));
+        assert_eq!(pay(9, RoundingMode::Down), Money::from_dollars(10));
+        assert_eq!(pay(9, RoundingMode::Up), Money::from_dollars(11));
+        assert_eq!(pay(9, RoundingMode::Nearest), Money::from_dollars(11));
+    }
+
+    #[test]
+    fn rounding_down_a_negative_amount_goes_away_from_zero() {
+        let amount = Money::from_dollars(-5);
+        let scaled = amount.scale(Ratio::THREE_TO_TWO, dollar_rounding(RoundingMode::Down));
+        assert_eq!(scaled, Money::from_dollars(-8));
+    }
+
+    #[test]
+    fn half_of_an_odd_number_of_cents() {
+        let amount = Money::from_cents(101);
+        assert_eq!(amount.half(PayoutRounding::default()), Money::from_cents(50));
+        let up = PayoutRounding::new(RoundingMode::Up, Money::from_cents(1));
+        assert_eq!(amount.half(up), Money::from_cents(51));
+    }
+
+    #[test]
+    #[should_panic(expected = "Money overflow")]
+    fn dollars_too_large_for_cents_overflow() {
+        Money::from_dollars(i64::MAX / 10);
+    }
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+use std::path::Path;
+
+// Error reading one of the line-based text formats, like index plays or card lists
+#[derive(Debug)]
+pub enum ParseError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io(error) => write!(f, "{}", error),
+            ParseError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[inline(always)]
+pub fn read_text<P: AsRef<Path>>(path: P) -> Result<String, ParseError> {
+    std::fs::read_to_string(path).map_err(ParseError::Io)
+}
+
+// Each line's whitespace separated tokens, numbered from one, with anything after
+// a # dropped as a comment. Lines without tokens are skipped
+pub fn token_lines(text: &str) -> impl Iterator<Item = (usize, Vec<&str>)> {
+    text.lines().enumerate().filter_map(|(line_index, line)| {
+        let line = match line.find('#') {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.is_empty() {
+            true => None,
+            false => Some((line_index + 1, tokens)),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        let text = "# index plays\n\nhard 16 vs 10 >= 0 stand # Illustrious 18\n  \ninsurance >= 3";
+        let lines: Vec<_> = token_lines(text).collect();
+        assert_eq!(
+            lines,
+            vec![
+                (3, vec!["hard", "16", "vs", "10", ">=", "0", "stand"]),
+                (5, vec!["insurance", ">=", "3"]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+use std::ops::AddAssign;
+use crate::money::*;
+
+// Counters collected while a Game is played, kept separate so results from
+// several threads can be merged into one report.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimulationResults {
+    pub rounds: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub pushes: usize,
+    pub hits: usize,
+    pub doubles: usize,
+    pub splits: usize,
+    pub stands: usize,
+    pub surrenders: usize,
+    pub blackjacks: usize,
+    pub insurances: usize,
+    pub even_money: usize,
+    pub initial_bet: Money,
+    pub amount_bet: Money,
+    pub amount_won: Money,
+    pub amount_lost: Money,
+}
+
+impl SimulationResults {
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline(always)]
+    pub fn merge(&mut self, other: &SimulationResults) {
+        self.rounds += other.rounds;
+        self.wins += other.wins;
+        self.losses += other.losses;
+        self.pushes += other.pushes;
+        self.hits += other.hits;
+        self.doubles += other.doubles;
+        self.splits += other.splits;
+        self.stands += other.stands;
+        self.surrenders += other.surrenders;
+        self.blackjacks += other.blackjacks;
+        self.insurances += other.insurances;
+        self.even_money += other.even_money;
+        self.initial_bet += other.initial_bet;
+        self.amount_bet += other.amount_bet;
+        self.amount_won += other.amount_won;
+        self.amount_lost += other.amount_lost;
+    }
+
+    // Amount won minus amount lost, from the player's point of view
+    #[inline(always)]
+    pub fn net(&self) -> Money {
+        self.amount_won - self.amount_lost
+    }
+
+    // House edge as a fraction of the initial bets, positive when the house is ahead
+    #[inline(always)]
+    pub fn house_edge(&self) -> f64 {
+        if self.initial_bet == Money::ZERO {
+            return 0.0;
+        }
+        -self.net().as_dollars() / self.initial_bet.as_dollars()
+    }
+
+    #[inline(always)]
+    pub fn ev_per_round(&self) -> f64 {
+        if self.rounds == 0 {
+            return 0.0;
+        }
+        self.net().as_dollars() / self.rounds as f64
+    }
+
+    #[inline(always)]
+    pub fn ev_per_100_rounds(&self) -> f64 {
+        self.ev_per_round() * 100.0
+    }
+}
+
+impl AddAssign<&SimulationResults> for SimulationResults {
+    #[inline(always)]
+    fn add_assign(&mut self, other: &SimulationResults) {
+        self.merge(other);
+    }
+}
+
+impl fmt::Display for SimulationResults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Rounds played: {}", self.rounds)?;
+        writeln!(f, "Wins: {}", self.wins)?;
+        writeln!(f, "Losses: {}", self.losses)?;
+        writeln!(f, "Pushes: {}", self.pushes)?;
+        writeln!(f, "Blackjacks: {}", self.blackjacks)?;
+        writeln!(f, "Hits: {}", self.hits)?;
+        writeln!(f, "Stands: {}", self.stands)?;
+        writeln!(f, "Doubles: {}", self.doubles)?;
+        writeln!(f, "Splits: {}", self.splits)?;
+        writeln!(f, "Surrenders: {}", self.surrenders)?;
+        writeln!(f, "Insurance Bets: {}", self.insurances)?;
+        writeln!(f, "Even Money: {}", self.even_money)?;
+        writeln!(f, "Amount Bet: {}", self.amount_bet)?;
+        writeln!(f, "Amount Won: {}", self.amount_won)?;
+        writeln!(f, "Amount Lost: {}", self.amount_lost)?;
+        writeln!(f, "Net Result: {}", self.net())?;
+        writeln!(f, "House Edge: {:.4}%", self.house_edge() * 100.0)?;
+        writeln!(f, "EV per round: {:.4}", self.ev_per_round())?;
+        write!(f, "EV per 100 rounds: {:.4}", self.ev_per_100_rounds())
+    }
+}
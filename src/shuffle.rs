@@ -0,0 +1,219 @@
+use rand::Rng;
+use smallvec::{SmallVec, smallvec};
+use crate::cardutils::*;
+
+// Stacks are kept with the top card last, the same way the shoe deals them
+type Stack = SmallVec<[Card; 4]>;
+
+// One step of a hand shuffle, applied to the whole stack
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShuffleStep {
+    // Cuts the stack roughly in half and riffles the halves together
+    Riffle,
+    // Pulls packets of about this many cards off the top onto a new pile, which
+    // reverses the order of the packets but not of the cards within them
+    Strip(usize),
+    // Moves the top of the stack, somewhere between a third and two thirds of it,
+    // to the bottom
+    Cut,
+    // Breaks the stack into zones, pairs each zone in the bottom half with one in
+    // the top half and riffles grabs of about grab cards off each pair in turn.
+    // Cards only mix with the zone they're paired with, which is what lets a
+    // tracker follow slugs of cards through the shuffle
+    ZoneRiffle { zones: usize, grab: usize },
+}
+
+// A shuffle built from the steps dealers use by hand, for studying shuffle
+// tracking and ace sequencing. Unlike a perfectly random shuffle, what comes out
+// depends on the order the cards were discarded in
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandShuffle {
+    // Push the cards left behind the cut card into the discards at a random
+    // place, rather than setting them on top
+    pub plug: bool,
+    pub steps: Vec<ShuffleStep>,
+}
+
+impl HandShuffle {
+    #[inline(always)]
+    pub fn new(plug: bool, steps: &[ShuffleStep]) -> Self {
+        HandShuffle {
+            plug,
+            steps: steps.to_vec(),
+        }
+    }
+
+    // A typical shoe game procedure: plug, riffle half deck grabs from two zones,
+    // strip, riffle the two zones again and cut
+    pub fn casino() -> Self {
+        Self::new(
+            true,
+            &[
+                ShuffleStep::ZoneRiffle { zones: 2, grab: 26 },
+                ShuffleStep::Strip(8),
+                ShuffleStep::ZoneRiffle { zones: 2, grab: 26 },
+                ShuffleStep::Cut,
+            ],
+        )
+    }
+
+    // Shuffles the discard tray together with the cards left in the shoe, both
+    // with their top card last, and returns the new shoe with its top card last
+    pub fn shuffle<R: Rng + ?Sized>(&self, stub: &[Card], discards: &[Card], rng: &mut R) -> Stack {
+        let mut stack: Stack = SmallVec::with_capacity(stub.len() + discards.len());
+        let plug_at = match self.plug {
+            true => rng.gen_range(0..=discards.len()),
+            false => discards.len(),
+        };
+        stack.extend_from_slice(&discards[..plug_at]);
+        stack.extend_from_slice(stub);
+        stack.extend_from_slice(&discards[plug_at..]);
+        for step in self.steps.iter() {
+            stack = match *step {
+                ShuffleStep::Riffle => {
+                    let half = binomial_half(stack.len(), rng);
+                    riffle(&stack[..half], &stack[half..], rng)
+                }
+                ShuffleStep::Strip(packet) => strip(&stack, packet, rng),
+                ShuffleStep::Cut => cut(&stack, rng),
+                ShuffleStep::ZoneRiffle { zones, grab } => zone_riffle(&stack, zones, grab, rng),
+            };
+        }
+        stack
+    }
+}
+
+// Where a dealer cuts a stack for a riffle, close to the middle
+#[inline(always)]
+fn binomial_half<R: Rng + ?Sized>(cards: usize, rng: &mut R) -> usize {
+    (0..cards).filter(|_| rng.gen_bool(0.5)).count()
+}
+
+// Somewhere between three quarters and one and a quarter of the size
+#[inline(always)]
+fn about<R: Rng + ?Sized>(size: usize, rng: &mut R) -> usize {
+    let size = size.max(1);
+    rng.gen_range((size * 3 / 4).max(1)..=size * 5 / 4)
+}
+
+// Gilbert-Shannon-Reeds riffle, each card drops from a packet with probability
+// proportional to the cards left in it
+fn riffle<R: Rng + ?Sized>(left: &[Card], right: &[Card], rng: &mut R) -> Stack {
+    let mut stack: Stack = SmallVec::with_capacity(left.len() + right.len());
+    let (mut left_index, mut right_index) = (0, 0);
+    while left_index < left.len() || right_index < right.len() {
+        let left_left = left.len() - left_index;
+        let right_left = right.len() - right_index;
+        if rng.gen_range(0..left_left + right_left) < left_left {
+            stack.push(left[left_index]);
+            left_index += 1;
+        } else {
+            stack.push(right[right_index]);
+            right_index += 1;
+        }
+    }
+    stack
+}
+
+fn strip<R: Rng + ?Sized>(stack: &[Card], packet: usize, rng: &mut R) -> Stack {
+    let mut stripped: Stack = SmallVec::with_capacity(stack.len());
+    let mut top = stack.len();
+    while top > 0 {
+        let bottom = top.saturating_sub(about(packet, rng));
+        stripped.extend_from_slice(&stack[bottom..top]);
+        top = bottom;
+    }
+    stripped
+}
+
+fn cut<R: Rng + ?Sized>(stack: &[Card], rng: &mut R) -> Stack {
+    let mut cut: Stack = SmallVec::from_slice(stack);
+    if !stack.is_empty() {
+        cut.rotate_left(rng.gen_range(stack.len() / 3..=stack.len() * 2 / 3));
+    }
+    cut
+}
+
+fn zone_riffle<R: Rng + ?Sized>(stack: &[Card], zones: usize, grab: usize, rng: &mut R) -> Stack {
+    let zones = zones.max(2);
+    let mut piles: SmallVec<[&[Card]; 8]> = smallvec![];
+    for zone in 0..zones {
+        piles.push(&stack[zone * stack.len() / zones..(zone + 1) * stack.len() / zones]);
+    }
+    let pairs = zones.div_ceil(2);
+    let mut shuffled: Stack = SmallVec::with_capacity(stack.len());
+    for pair in 0..pairs {
+        let mut lower = piles[pair];
+        let mut upper = piles.get(pair + pairs).copied().unwrap_or(&[]);
+        while !lower.is_empty() || !upper.is_empty() {
+            let (rest, lower_grab) = lower.split_at(lower.len().saturating_sub(about(grab, rng)));
+            let (rest_upper, upper_grab) =
+                upper.split_at(upper.len().saturating_sub(about(grab, rng)));
+            shuffled.extend(riffle(lower_grab, upper_grab, rng));
+            lower = rest;
+            upper = rest_upper;
+        }
+    }
+    shuffled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn sorted(cards: &[Card]) -> Vec<String> {
+        let mut cards: Vec<String> = cards.iter().map(|card| format!("{:?}", card)).collect();
+        cards.sort();
+        cards
+    }
+
+    #[test]
+    fn every_step_keeps_the_cards() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut shoe = Shoe::new(6, &mut rng);
+        let mut discards: Stack = smallvec![];
+        while shoe.cards_left() > 60 {
+            discards.push(shoe.deal());
+        }
+        let mut stub: Stack = smallvec![];
+        while shoe.cards_left() > 0 {
+            stub.push(shoe.deal());
+        }
+        let mut all: Stack = discards.clone();
+        all.extend_from_slice(&stub);
+        let steps = [
+            ShuffleStep::Riffle,
+            ShuffleStep::Strip(5),
+            ShuffleStep::Cut,
+            ShuffleStep::ZoneRiffle { zones: 3, grab: 20 },
+        ];
+        for step in steps {
+            let shuffled = HandShuffle::new(true, &[step]).shuffle(&stub, &discards, &mut rng);
+            assert_eq!(sorted(&shuffled), sorted(&all), "{:?}", step);
+        }
+        let shuffled = HandShuffle::casino().shuffle(&stub, &discards, &mut rng);
+        assert_eq!(sorted(&shuffled), sorted(&all));
+    }
+
+    #[test]
+    fn riffle_keeps_each_packet_in_order() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let left: Stack = (2..=10)
+            .map(|n| Card::new(Rank::Number(n), Suit::Spades))
+            .collect();
+        let right: Stack = (2..=10)
+            .map(|n| Card::new(Rank::Number(n), Suit::Hearts))
+            .collect();
+        let riffled = riffle(&left, &right, &mut rng);
+        for suit in [Suit::Spades, Suit::Hearts] {
+            let packet: Vec<u8> = riffled
+                .iter()
+                .filter(|card| card.get_suit() == suit)
+                .map(|card| card.value())
+                .collect();
+            assert_eq!(packet, (2..=10).collect::<Vec<u8>>());
+        }
+    }
+}
@@ -0,0 +1,336 @@
+use crate::analysis::*;
+use crate::basicstrategy::*;
+use crate::gamelogic::*;
+use Decision::*;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct CellValues {
+    weight: f64,
+    stand: f64,
+    hit: f64,
+    double: f64,
+}
+
+impl CellValues {
+    #[inline(always)]
+    fn add(&mut self, weight: f64, stand: f64, hit: f64, double: f64) {
+        self.weight += weight;
+        self.stand += weight * stand;
+        self.hit += weight * hit;
+        self.double += weight * double;
+    }
+
+    // Value of the best of standing, hitting and doubling
+    #[inline(always)]
+    fn best(&self) -> f64 {
+        self.stand.max(self.hit).max(self.double) / self.weight
+    }
+
+    #[inline(always)]
+    fn decision(&self) -> Decision {
+        let weight = if self.weight > 0.0 { self.weight } else { 1.0 };
+        choose(
+            self.stand / weight,
+            self.hit / weight,
+            self.double / weight,
+            None,
+        )
+    }
+}
+
+// Picks the best play, surrendering included, along with what to fall back on
+// when doubling or surrendering aren't allowed
+fn choose(stand: f64, hit: f64, double: f64, split: Option<f64>) -> Decision {
+    let split = split.unwrap_or(f64::NEG_INFINITY);
+    let without_surrender = if split > stand.max(hit).max(double) {
+        Split
+    } else if double > stand.max(hit) {
+        if hit >= stand {
+            Double
+        } else {
+            DoubleOrStand
+        }
+    } else if hit > stand {
+        Hit
+    } else {
+        Stand
+    };
+    if -0.5 <= stand.max(hit).max(double).max(split) {
+        return without_surrender;
+    }
+    let fallback = stand.max(hit).max(split);
+    if fallback == split {
+        SurrenderOrSplit
+    } else if fallback == hit {
+        SurrenderOrHit
+    } else {
+        SurrenderOrStand
+    }
+}
+
+// Folds the plays with and without doubling after splits into one table entry
+#[inline(always)]
+fn combine_das(das: Decision, no_das: Decision, double_after_split: bool) -> Decision {
+    match (das, no_das) {
+        _ if das == no_das => das,
+        (Split, Hit) => SplitIfDASOrHit,
+        (Split, Double) => SplitIfDASOrDouble,
+        (Split, Stand) => SplitIfDASOrStand,
+        (Split, SurrenderOrSplit) => SplitIfDASOrSurrender,
+        _ if double_after_split => das,
+        _ => no_das,
+    }
+}
+
+impl BasicStrategyLUT {
+    // Total-dependent basic strategy for the rules. Each table entry is the play
+    // with the best expected value averaged over every two card hand making that
+    // total, weighted by how likely the hand is with the upcard out of the shoe.
+    // Pairs only count toward a total when no other two card hand makes it.
+    //
+    // Like the hand-written tables, the result covers games with and without
+    // surrender and doubling after splits, so in a peek game with the same deck
+    // count and soft 17 rule the two can be compared entry for entry.
+    pub fn generate(rules: &GameRules) -> BasicStrategyLUT {
+        let mut lut = BasicStrategyLUT {
+            hard: [[Hit; 10]; 15],
+            soft: [[Hit; 10]; 9],
+            pair: [[Hit; 10]; 10],
+        };
+        for upcard in 0..10 {
+            lut.generate_column(rules, upcard);
+        }
+        lut
+    }
+
+    // Fills in the entries against one upcard, indexed like counting::rank_index
+    pub(crate) fn generate_column(&mut self, rules: &GameRules, upcard: usize) {
+        let mut das_rules = rules.clone();
+        das_rules.double_after_split = true;
+        let mut no_das_rules = rules.clone();
+        no_das_rules.double_after_split = false;
+        let dealer_index = match upcard {
+            0 => 9,
+            _ => upcard - 1,
+        };
+        let mut shoe = Composition::new(rules.decks_in_shoe);
+        shoe.remove(upcard);
+        let mut evaluator = Evaluator::new(rules, shoe, upcard);
+        let mut hard = [CellValues::default(); 15];
+        let mut soft = [CellValues::default(); 9];
+        let mut hard_pairs = [CellValues::default(); 15];
+        let mut soft_pairs = [CellValues::default(); 9];
+        for first in 0..10 {
+            for second in first..10 {
+                let weight = if first == second {
+                    shoe.counts[first] as f64 * (shoe.counts[first] as f64 - 1.0)
+                } else {
+                    2.0 * shoe.counts[first] as f64 * shoe.counts[second] as f64
+                };
+                if weight <= 0.0 || (first == 0 && second == 9) {
+                    continue;
+                }
+                let hand = HandState::default().push(first).push(second);
+                let stand = evaluator.stand(hand);
+                let stand = evaluator.settle_hole_card(&hand, stand, 1.0);
+                let hit = evaluator.hit(hand);
+                let hit = evaluator.settle_hole_card(&hand, hit, 1.0);
+                let double = match rules.doubling.allows(2, hand.total() as u8) {
+                    true => evaluator.double(hand),
+                    false => f64::NEG_INFINITY,
+                };
+                let double = evaluator.settle_hole_card(&hand, double, 2.0);
+                let total = hand.total() as usize;
+                let (cells, pair_cells, index): (&mut [CellValues], &mut [CellValues], _) =
+                    match hand.is_soft() {
+                        true => (&mut soft, &mut soft_pairs, (total - 12).min(8)),
+                        false => (&mut hard, &mut hard_pairs, (total - 4).min(14)),
+                    };
+                if first != second {
+                    cells[index].add(weight, stand, hit, double);
+                    continue;
+                }
+                pair_cells[index].add(weight, stand, hit, double);
+                let pair_index = match first {
+                    0 => 9,
+                    _ => first - 1,
+                };
+                let das = split_value(&das_rules, shoe, upcard, first);
+                let no_das = split_value(&no_das_rules, shoe, upcard, first);
+                self.pair[pair_index][dealer_index] = combine_das(
+                    choose(stand, hit, double, Some(das)),
+                    choose(stand, hit, double, Some(no_das)),
+                    rules.double_after_split,
+                );
+            }
+        }
+        for (index, cell) in hard.iter().enumerate() {
+            let cell = if cell.weight > 0.0 {
+                cell
+            } else {
+                &hard_pairs[index]
+            };
+            self.hard[index][dealer_index] = cell.decision();
+        }
+        for (index, cell) in soft.iter().enumerate() {
+            let cell = if cell.weight > 0.0 {
+                cell
+            } else {
+                &soft_pairs[index]
+            };
+            self.soft[index][dealer_index] = cell.decision();
+        }
+    }
+
+    // Generates the strategy for the rules and makes it the one games play by
+    pub fn cache_generated(rules: &GameRules) {
+        Self::cache_lut(Box::leak(Box::new(Self::generate(rules))));
+    }
+}
+
+impl EarlySurrenderLUT {
+    // Early surrender strategy for the rules, surrendering any total or pair whose
+    // best play is worth less than half a bet before the dealer checks for
+    // blackjack. Hands are averaged by total as in BasicStrategyLUT::generate
+    pub fn generate(rules: &GameRules) -> EarlySurrenderLUT {
+        let mut lut = EarlySurrenderLUT {
+            hard: [[false; 10]; 15],
+            pair: [[false; 10]; 10],
+        };
+        for upcard in 0..10 {
+            lut.generate_column(rules, upcard);
+        }
+        lut
+    }
+
+    // Fills in the entries against one upcard, indexed like counting::rank_index
+    pub(crate) fn generate_column(&mut self, rules: &GameRules, upcard: usize) {
+        // Surrendering comes before the peek, so a dealer blackjack is still
+        // possible and takes the original bet
+        let mut rules = rules.clone();
+        if rules.hole_card == HoleCard::Peek {
+            rules.hole_card = HoleCard::OriginalBetsOnly;
+        }
+        let rules = &rules;
+        let dealer_index = match upcard {
+            0 => 9,
+            _ => upcard - 1,
+        };
+        let mut shoe = Composition::new(rules.decks_in_shoe);
+        shoe.remove(upcard);
+        let mut evaluator = Evaluator::new(rules, shoe, upcard);
+        let mut hard = [CellValues::default(); 15];
+        for first in 0..10 {
+            for second in first..10 {
+                let weight = if first == second {
+                    shoe.counts[first] as f64 * (shoe.counts[first] as f64 - 1.0)
+                } else {
+                    2.0 * shoe.counts[first] as f64 * shoe.counts[second] as f64
+                };
+                let hand = HandState::default().push(first).push(second);
+                if weight <= 0.0 || hand.is_soft() {
+                    continue;
+                }
+                let stand = evaluator.stand(hand);
+                let stand = evaluator.settle_hole_card(&hand, stand, 1.0);
+                let hit = evaluator.hit(hand);
+                let hit = evaluator.settle_hole_card(&hand, hit, 1.0);
+                let double = match rules.doubling.allows(2, hand.total() as u8) {
+                    true => evaluator.double(hand),
+                    false => f64::NEG_INFINITY,
+                };
+                let double = evaluator.settle_hole_card(&hand, double, 2.0);
+                if first != second {
+                    let index = (hand.total() as usize - 4).min(14);
+                    hard[index].add(weight, stand, hit, double);
+                    continue;
+                }
+                let pair_index = match first {
+                    0 => 9,
+                    _ => first - 1,
+                };
+                let split = split_value(rules, shoe, upcard, first);
+                let best = stand.max(hit).max(double).max(split);
+                self.pair[pair_index][dealer_index] = best < -0.5;
+            }
+        }
+        for (index, cell) in hard.iter().enumerate() {
+            self.hard[index][dealer_index] = cell.weight > 0.0 && cell.best() < -0.5;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(decks_in_shoe: u8, hit_soft_17: bool) -> GameRules {
+        let penetration = Penetration::Fraction(0.75);
+        GameRules::new(decks_in_shoe, penetration, hit_soft_17, true, None, false, false, false)
+    }
+
+    // Generating a whole table is slow unoptimized, so the default run only
+    // regenerates the column against a ten over the hand-written table
+    #[test]
+    fn generated_six_deck_s17_tables_match_the_hand_written_ones_against_a_ten() {
+        let rules = rules(6, false);
+        let mut generated = BasicStrategyLUT::for_rules(&rules).clone();
+        generated.generate_column(&rules, 9);
+        assert_eq!(generated, *BasicStrategyLUT::for_rules(&rules));
+        let mut generated = EarlySurrenderLUT::for_rules(&rules).clone();
+        generated.generate_column(&rules, 9);
+        assert_eq!(generated, *EarlySurrenderLUT::for_rules(&rules));
+    }
+
+    #[test]
+    fn single_deck_early_surrender_against_a_ten_matches_with_and_without_das() {
+        for hit_soft_17 in [false, true] {
+            for double_after_split in [false, true] {
+                let mut rules = rules(1, hit_soft_17);
+                rules.double_after_split = double_after_split;
+                let mut generated = EarlySurrenderLUT::for_rules(&rules).clone();
+                generated.generate_column(&rules, 9);
+                assert_eq!(
+                    generated,
+                    *EarlySurrenderLUT::for_rules(&rules),
+                    "hit soft 17: {}, das: {}",
+                    hit_soft_17,
+                    double_after_split
+                );
+            }
+        }
+    }
+
+    // Every deck count, soft 17 rule and, where it changes the early surrender
+    // table, doubling after splits rule. Run it with cargo test --release -- --ignored
+    #[test]
+    #[ignore]
+    fn generated_tables_match_the_hand_written_ones() {
+        for decks_in_shoe in [1, 2, 4, 6, 8] {
+            for hit_soft_17 in [false, true] {
+                let mut rules = rules(decks_in_shoe, hit_soft_17);
+                assert_eq!(
+                    BasicStrategyLUT::generate(&rules),
+                    *BasicStrategyLUT::for_rules(&rules),
+                    "decks: {}, hit soft 17: {}",
+                    decks_in_shoe,
+                    hit_soft_17
+                );
+                for double_after_split in [true, false] {
+                    if decks_in_shoe > 1 && !double_after_split {
+                        continue;
+                    }
+                    rules.double_after_split = double_after_split;
+                    assert_eq!(
+                        EarlySurrenderLUT::generate(&rules),
+                        *EarlySurrenderLUT::for_rules(&rules),
+                        "decks: {}, hit soft 17: {}, das: {}",
+                        decks_in_shoe,
+                        hit_soft_17,
+                        double_after_split
+                    );
+                }
+            }
+        }
+    }
+}